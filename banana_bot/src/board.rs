@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::dictionary::Dictionary;
+use crate::tiles::Tiles;
+
+//sparse grid of placed letters
+//(0,0) is the position of the first word, x grows to the right and y grows down
+#[derive(Clone)]
+pub struct Grid {
+    pub(crate) cells: HashMap<(i32, i32), char>, //letter at each occupied cell
+    pub(crate) min_x: i32,
+    pub(crate) min_y: i32,
+    pub(crate) max_x: i32,
+    pub(crate) max_y: i32,
+}
+
+#[derive(Clone)]
+pub struct Board {
+    pub tiles: Tiles, //all tiles currently in board
+    pub remaining: Tiles, //not linked
+    pub placements: Vec<Placement>, //linked words in the order they were laid down
+    pub grid: Grid, //letters actually laid out on the table
+}
+
+//which way a word reads
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+//word laid out on the board, origin is the square of its first letter
+//ex. origin (1,5) going Down means the word starts at 1,5 and is vertical
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Placement {
+    pub origin: (i32, i32),
+    pub direction: Direction,
+    pub word: String,
+}
+
+//what is left to search from a board: the tiles in hand and the letters on the table
+//shifted so the top left corner is (0,0), the same layout reached through different word orders is equal
+//a board can also be compared with rows and columns swapped, every word still reads the same way
+//mirroring it any other way would spell the words backwards
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Position {
+    remaining: Tiles,
+    cells: Vec<(i32, i32, char)>,
+}
+
+//run of letters on a board that is not a word
+pub struct InvalidWord {
+    pub run: Placement,
+}
+
+impl Direction {
+    //one square along the word
+    pub fn step(self) -> (i32, i32) {
+        match self {
+            Direction::Across => (1, 0),
+            Direction::Down => (0, 1),
+        }
+    }
+
+    //direction cross words read in
+    pub fn cross(self) -> Direction {
+        match self {
+            Direction::Across => Direction::Down,
+            Direction::Down => Direction::Across,
+        }
+    }
+}
+
+impl Placement {
+    pub fn new(word: &str, origin: (i32, i32), direction: Direction) -> Placement {
+        Placement {
+            origin,
+            direction,
+            word: word.to_string(),
+        }
+    }
+
+    //square of every letter, first letter first
+    pub fn cells(&self) -> impl Iterator<Item = ((i32, i32), char)> + '_ {
+        let (x, y) = self.origin;
+        let (dx, dy) = self.direction.step();
+        self.word.chars().enumerate().map(move |(i, c)| ((x + dx * i as i32, y + dy * i as i32), c))
+    }
+
+    //square of the last letter
+    pub fn end(&self) -> (i32, i32) {
+        let (x, y) = self.origin;
+        let (dx, dy) = self.direction.step();
+        let last = self.word.len() as i32 - 1;
+        (x + dx * last, y + dy * last)
+    }
+
+    pub fn letter_at(&self, cell: (i32, i32)) -> Option<char> {
+        let (x, y) = self.origin;
+        let (ex, ey) = self.end();
+        if cell.0 < x || cell.0 > ex || cell.1 < y || cell.1 > ey {
+            return None;
+        }
+        let i = (cell.0 - x + cell.1 - y) as usize;
+        self.word[i..].chars().next()
+    }
+
+    //first square both words cover, None if they don't touch
+    pub fn intersection(&self, other: &Placement) -> Option<(i32, i32)> {
+        self.cells().map(|(cell, _)| cell).find(|&cell| other.letter_at(cell).is_some())
+    }
+
+    //true if the two words have the same letter on every square they share
+    pub fn agrees_with(&self, other: &Placement) -> bool {
+        self.cells().all(|(cell, c)| other.letter_at(cell).is_none_or(|o| o == c))
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Grid {
+        Grid::new()
+    }
+}
+
+impl Grid {
+    pub fn new() -> Grid {
+        Grid {
+            cells: HashMap::new(),
+            min_x: 0,
+            min_y: 0,
+            max_x: 0,
+            max_y: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<char> {
+        self.cells.get(&(x, y)).copied()
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, c: char) {
+        if self.cells.is_empty() {
+            self.min_x = x;
+            self.max_x = x;
+            self.min_y = y;
+            self.max_y = y;
+        }
+        else {
+            self.min_x = self.min_x.min(x);
+            self.max_x = self.max_x.max(x);
+            self.min_y = self.min_y.min(y);
+            self.max_y = self.max_y.max(y);
+        }
+        self.cells.insert((x, y), c);
+    }
+
+    //letters of the run through (x,y) in the given direction, including the letter c placed at (x,y)
+    pub fn run_through(&self, x: i32, y: i32, direction: Direction, c: char) -> String {
+        let (dx, dy) = direction.step();
+
+        //walk back to the start of the run
+        let mut start_x = x;
+        let mut start_y = y;
+        while self.get(start_x - dx, start_y - dy).is_some() {
+            start_x -= dx;
+            start_y -= dy;
+        }
+
+        let mut run = String::new();
+        let mut cx = start_x;
+        let mut cy = start_y;
+        loop {
+            if cx == x && cy == y {
+                run.push(c);
+            }
+            else if let Some(l) = self.get(cx, cy) {
+                run.push(l);
+            }
+            else {
+                break;
+            }
+            cx += dx;
+            cy += dy;
+        }
+        run
+    }
+
+    //every maximal horizontal and vertical run of two or more letters
+    pub fn runs(&self) -> Vec<Placement> {
+        let mut runs = Vec::new();
+        for (&(x, y), _) in self.cells.iter() {
+            for direction in [Direction::Across, Direction::Down] {
+                let (dx, dy) = direction.step();
+
+                //only start counting from the first letter of a run
+                if self.get(x - dx, y - dy).is_some() {
+                    continue;
+                }
+
+                let mut run = String::new();
+                let mut cx = x;
+                let mut cy = y;
+                while let Some(l) = self.get(cx, cy) {
+                    run.push(l);
+                    cx += dx;
+                    cy += dy;
+                }
+                if run.len() > 1 {
+                    runs.push(Placement::new(&run, (x, y), direction));
+                }
+            }
+        }
+        //hash map order is arbitrary so sort for stable reports
+        runs.sort_by_key(|r| (r.origin.1, r.origin.0, r.direction));
+        runs
+    }
+
+    pub fn place(&mut self, placement: &Placement) {
+        for ((x, y), c) in placement.cells() {
+            self.set(x, y, c);
+        }
+    }
+
+    //true if every letter can be reached from every other letter through neighbouring letters
+    pub fn is_connected(&self) -> bool {
+        let start = match self.cells.keys().next() {
+            Some(&start) => start,
+            None => return true,
+        };
+
+        let mut seen = HashSet::new();
+        let mut stack = vec![start];
+        seen.insert(start);
+        while let Some((x, y)) = stack.pop() {
+            for next in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if self.cells.contains_key(&next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen.len() == self.cells.len()
+    }
+
+    //every letter relative to the top left corner, in row order
+    pub fn normalized(&self) -> Vec<(i32, i32, char)> {
+        let mut cells: Vec<(i32, i32, char)> = self.cells.iter()
+            .map(|(&(x, y), &c)| (y - self.min_y, x - self.min_x, c))
+            .collect();
+        cells.sort_unstable();
+        cells.into_iter().map(|(y, x, c)| (x, y, c)).collect()
+    }
+
+    //same as normalized with rows and columns swapped
+    pub fn normalized_transposed(&self) -> Vec<(i32, i32, char)> {
+        let mut cells: Vec<(i32, i32, char)> = self.cells.iter()
+            .map(|(&(x, y), &c)| (x - self.min_x, y - self.min_y, c))
+            .collect();
+        cells.sort_unstable();
+        cells.into_iter().map(|(y, x, c)| (x, y, c)).collect()
+    }
+}
+
+impl Board {
+    //transposed boards are equal when transpose is set
+    pub fn position(&self, transpose: bool) -> Position {
+        let mut cells = self.grid.normalized();
+        if transpose {
+            cells = cells.min(self.grid.normalized_transposed());
+        }
+        Position {
+            remaining: self.remaining,
+            cells,
+        }
+    }
+
+    //copy of the board with word i picked up and its tiles back in the hand
+    //letters shared with other words stay on the table, None if what is left falls apart
+    pub fn without_word(&self, i: usize) -> Option<Board> {
+        let mut b = self.clone();
+        b.placements.remove(i);
+
+        b.grid = Grid::new();
+        for placement in &b.placements {
+            b.grid.place(placement);
+        }
+        if b.grid.is_empty() || !b.grid.is_connected() {
+            return None;
+        }
+
+        for (&(x, y), &c) in self.grid.cells.iter() {
+            if b.grid.get(x, y).is_none() {
+                b.remaining.add(c).ok()?;
+            }
+        }
+        Some(b)
+    }
+
+    //tiles of the other board that are not on this board with the same letter in the same cell
+    pub fn moved_from(&self, other: &Board) -> usize {
+        other.grid.cells.iter().filter(|&(&(x, y), &c)| self.grid.get(x, y) != Some(c)).count()
+    }
+
+    //true if every word agrees with the letters on the grid and with every word it crosses
+    pub fn is_consistent(&self) -> bool {
+        let on_grid = self.placements.iter().all(|p| p.cells().all(|((x, y), c)| self.grid.get(x, y) == Some(c)));
+        let crossings = self.placements.iter().enumerate()
+            .all(|(i, p)| self.placements[i + 1..].iter().all(|other| p.agrees_with(other)));
+        on_grid && crossings
+    }
+}
+
+//check every horizontal and vertical run on the board against the dictionary
+pub fn validate(dictionary: &Dictionary, b: &Board) -> Result<(), Vec<InvalidWord>> {
+    let runs = b.grid.runs();
+
+    let mut invalid = Vec::new();
+    for run in runs {
+        if !dictionary.is_word(&run.word) {
+            invalid.push(InvalidWord { run });
+        }
+    }
+
+    if invalid.is_empty() {
+        Ok(())
+    }
+    else {
+        Err(invalid)
+    }
+}
+
+//letters row by row with blanks for empty cells, then the tiles still in hand
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //grid keeps track of its own bounds
+        for y in self.grid.min_y..=self.grid.max_y {
+            for x in self.grid.min_x..=self.grid.max_x {
+                write!(f, "{}", self.grid.get(x, y).unwrap_or(' '))?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        write!(f, "Remaining letters: {}", self.remaining)
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Across => write!(f, "across"),
+            Direction::Down => write!(f, "down"),
+        }
+    }
+}
+
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at ({}, {}) {}", self.word, self.origin.0, self.origin.1, self.direction)
+    }
+}
+
+impl fmt::Display for InvalidWord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_cells_and_end() {
+        let across = Placement::new("cat", (2, -1), Direction::Across);
+        assert_eq!(across.cells().collect::<Vec<_>>(), [((2, -1), 'c'), ((3, -1), 'a'), ((4, -1), 't')]);
+        assert_eq!(across.end(), (4, -1));
+
+        let down = Placement::new("dog", (0, 0), Direction::Down);
+        assert_eq!(down.cells().collect::<Vec<_>>(), [((0, 0), 'd'), ((0, 1), 'o'), ((0, 2), 'g')]);
+        assert_eq!(down.end(), (0, 2));
+    }
+
+    #[test]
+    fn placement_letter_at() {
+        let down = Placement::new("dog", (1, 1), Direction::Down);
+        assert_eq!(down.letter_at((1, 1)), Some('d'));
+        assert_eq!(down.letter_at((1, 3)), Some('g'));
+        assert_eq!(down.letter_at((1, 0)), None);
+        assert_eq!(down.letter_at((1, 4)), None);
+        assert_eq!(down.letter_at((2, 2)), None);
+    }
+
+    #[test]
+    fn placement_intersection() {
+        let cat = Placement::new("cat", (0, 0), Direction::Across);
+        let tag = Placement::new("tag", (2, 0), Direction::Down);
+        let dog = Placement::new("dog", (0, 2), Direction::Across);
+        assert_eq!(cat.intersection(&tag), Some((2, 0)));
+        assert_eq!(tag.intersection(&cat), Some((2, 0)));
+        assert_eq!(cat.intersection(&dog), None);
+        assert_eq!(tag.intersection(&dog), Some((2, 2)));
+    }
+
+    #[test]
+    fn placement_agrees_with() {
+        let cat = Placement::new("cat", (0, 0), Direction::Across);
+        assert!(cat.agrees_with(&Placement::new("tag", (2, 0), Direction::Down)));
+        assert!(!cat.agrees_with(&Placement::new("dog", (2, 0), Direction::Down)));
+        assert!(cat.agrees_with(&Placement::new("dog", (0, 2), Direction::Across)));
+    }
+
+    #[test]
+    fn board_consistency() {
+        let mut b = Board {
+            tiles: Tiles::from_letters("catg").unwrap(),
+            remaining: Tiles::new(),
+            placements: Vec::new(),
+            grid: Grid::new(),
+        };
+        for placement in [Placement::new("cat", (0, 0), Direction::Across), Placement::new("tag", (2, 0), Direction::Down)] {
+            b.grid.place(&placement);
+            b.placements.push(placement);
+        }
+        assert!(b.is_consistent());
+
+        //a word that crosses another on a different letter
+        b.placements.push(Placement::new("dog", (0, 0), Direction::Down));
+        assert!(!b.is_consistent());
+    }
+
+    fn board_of(tiles: &str, placements: &[Placement]) -> Board {
+        let mut b = Board {
+            tiles: Tiles::from_letters(tiles).unwrap(),
+            remaining: Tiles::new(),
+            placements: Vec::new(),
+            grid: Grid::new(),
+        };
+        for placement in placements {
+            b.grid.place(placement);
+            b.placements.push(placement.clone());
+        }
+        b
+    }
+
+    #[test]
+    fn without_word_and_moved_tiles() {
+        let cat = Placement::new("cat", (0, 0), Direction::Across);
+        let table = board_of("catg", &[cat.clone(), Placement::new("tag", (2, 0), Direction::Down)]);
+
+        //the t is shared with cat so it stays
+        let picked_up = table.without_word(1).unwrap();
+        assert_eq!(picked_up.placements.len(), 1);
+        assert_eq!(picked_up.placements[0], cat);
+        assert_eq!(picked_up.remaining, Tiles::from_letters("ag").unwrap());
+        assert_eq!(picked_up.moved_from(&table), 2);
+
+        //putting the a back in its cell only moves the g
+        let rebuilt = board_of("catb", &[cat, Placement::new("tab", (2, 0), Direction::Down)]);
+        assert_eq!(rebuilt.moved_from(&table), 1);
+        assert_eq!(table.moved_from(&table), 0);
+
+        //picking up the only word leaves nothing on the table
+        assert!(board_of("cat", &[Placement::new("cat", (0, 0), Direction::Across)]).without_word(0).is_none());
+    }
+}
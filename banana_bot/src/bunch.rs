@@ -0,0 +1,103 @@
+use crate::board::Board;
+use crate::dictionary::Dictionary;
+use crate::movegen::{generate_moves, playable_letters};
+use crate::tiles::Tiles;
+
+//number of each letter a-z in a standard 144 tile game
+pub static STANDARD_DISTRIBUTION: [u8; 26] = [13, 3, 3, 6, 18, 3, 4, 3, 12, 2, 2, 5, 3, 8, 11, 3, 2, 9, 6, 9, 6, 3, 3, 2, 3, 2];
+
+//tiles the game started with and the ones we have been dealt so far
+//tiles we haven't seen are either in the bunch or in another player's hand,
+//either way each of them is equally likely to be the next one we draw
+#[derive(Clone)]
+pub struct Bunch {
+    distribution: Tiles,
+    seen: Tiles,
+}
+
+impl Bunch {
+    pub fn standard() -> Bunch {
+        let mut distribution = Tiles::new();
+        for (i, &n) in STANDARD_DISTRIBUTION.iter().enumerate() {
+            for _ in 0..n {
+                distribution.add((b'a' + i as u8) as char).expect("distribution only has letters a-z");
+            }
+        }
+        Bunch::with_distribution(distribution)
+    }
+
+    //for other editions of the game
+    pub fn with_distribution(distribution: Tiles) -> Bunch {
+        Bunch {
+            distribution,
+            seen: Tiles::new(),
+        }
+    }
+
+    pub fn see(&mut self, tiles: &Tiles) {
+        self.seen.add_all(tiles).expect("tiles seen are never more than the letters");
+    }
+
+    pub fn clear_seen(&mut self) {
+        self.seen = Tiles::new();
+    }
+
+    pub fn seen(&self) -> &Tiles {
+        &self.seen
+    }
+
+    //tiles we have not been dealt yet
+    pub fn unseen(&self) -> Tiles {
+        self.distribution.difference(&self.seen)
+    }
+
+    //chance that the next tile we draw is the letter
+    pub fn probability(&self, c: char) -> f64 {
+        let unseen = self.unseen();
+        if unseen.is_empty() {
+            return 0.0;
+        }
+        unseen.count(c) as f64 / unseen.len() as f64
+    }
+
+    //chance of every letter a-z being the next tile we draw
+    pub fn probabilities(&self) -> [f64; 26] {
+        let mut probabilities = [0.0; 26];
+        for (i, p) in probabilities.iter_mut().enumerate() {
+            *p = self.probability((b'a' + i as u8) as char);
+        }
+        probabilities
+    }
+}
+
+//chance that the board can take the next tile we draw without moving anything
+//a letter counts if some placement on the board uses it together with the remaining tiles
+pub fn flexibility(dictionary: &Dictionary, b: &Board, bunch: &Bunch) -> f64 {
+    if b.grid.is_empty() {
+        return 0.0;
+    }
+
+    //laying the tile down on its own is much cheaper to check than every word it can make with the rack
+    let playable = playable_letters(dictionary, &b.grid);
+
+    let mut flexibility = 0.0;
+    for (i, p) in bunch.probabilities().iter().enumerate() {
+        if *p == 0.0 {
+            continue;
+        }
+        if playable & (1 << i) != 0 {
+            flexibility += p;
+            continue;
+        }
+        let c = (b'a' + i as u8) as char;
+        let mut rack = b.remaining;
+        //a hand that already holds 255 of the letter can't take another
+        if rack.add(c).is_err() {
+            continue;
+        }
+        if generate_moves(dictionary, &b.grid, &rack).iter().any(|m| m.used.count(c) > b.remaining.count(c)) {
+            flexibility += p;
+        }
+    }
+    flexibility
+}
@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+//how wide and how long the solver searches
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchConfig {
+    pub branching: Vec<usize>, //placements followed at each level when building on the current boards, the last one repeats
+    pub restart_branching: Vec<usize>, //same when starting over from an empty board
+    pub first_words: usize, //candidates for the first word on an empty board
+    pub min_word_length: usize, //shorter words are left for the finishing pass
+    pub max_boards: usize, //boards kept after each solve
+    pub time_budget: Option<Duration>, //boards found so far are kept once it runs out
+    pub threads: usize, //threads searching at once, 0 uses every core
+    pub deterministic: bool, //boards come out in the same order on every run, unless time runs out
+    pub dedupe_transposed: bool, //a board and the same board with rows and columns swapped count as one
+    pub peel_branching: usize, //placements followed at each level when a peel builds on the board on the table
+    pub peel_depth: usize, //words a peel lays down at most
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    UnknownSetting(String),
+    InvalidValue(String, String), //setting and the value that could not be used
+}
+
+//names that can be passed to SearchConfig::set
+pub static SETTING_NAMES: [&str; 12] = [
+    "mode", "branching", "restart-branching", "first-words", "min-word-length", "max-boards", "time-budget", "threads", "deterministic", "dedupe-transposed",
+    "peel-branching", "peel-depth",
+];
+
+impl SearchConfig {
+    //quick answers while the game is running
+    pub fn speed() -> SearchConfig {
+        SearchConfig {
+            branching: vec![3, 2],
+            restart_branching: vec![2],
+            first_words: 2,
+            min_word_length: 5,
+            max_boards: 10,
+            time_budget: Some(Duration::from_secs(1)),
+            threads: 0,
+            deterministic: false,
+            dedupe_transposed: false,
+            peel_branching: 2,
+            peel_depth: 4,
+        }
+    }
+
+    //wider search for the endgame
+    pub fn deep() -> SearchConfig {
+        SearchConfig {
+            branching: vec![10, 8, 6],
+            restart_branching: vec![5, 4, 3],
+            first_words: 5,
+            min_word_length: 4,
+            max_boards: 30,
+            time_budget: Some(Duration::from_secs(10)),
+            threads: 0,
+            deterministic: false,
+            dedupe_transposed: false,
+            peel_branching: 4,
+            peel_depth: 6,
+        }
+    }
+
+    //placements to follow at a level of the search
+    pub fn branches(branching: &[usize], level: usize) -> usize {
+        branching.get(level).or(branching.last()).copied().unwrap_or(1)
+    }
+
+    //change one setting by name, values are parsed the same way for flags and the repl
+    pub fn set(&mut self, setting: &str, value: &str) -> Result<(), ConfigError> {
+        let invalid = || ConfigError::InvalidValue(setting.to_string(), value.to_string());
+        match setting {
+            "mode" => {
+                *self = match value {
+                    "speed" => SearchConfig::speed(),
+                    "default" => SearchConfig::default(),
+                    "deep" => SearchConfig::deep(),
+                    _ => return Err(invalid()),
+                }
+            }
+            "branching" => self.branching = parse_branching(value).ok_or_else(invalid)?,
+            "restart-branching" => self.restart_branching = parse_branching(value).ok_or_else(invalid)?,
+            "first-words" => self.first_words = parse_positive(value).ok_or_else(invalid)?,
+            "min-word-length" => self.min_word_length = parse_positive(value).ok_or_else(invalid)?,
+            "max-boards" => self.max_boards = parse_positive(value).ok_or_else(invalid)?,
+            "time-budget" => {
+                self.time_budget = match value {
+                    "none" | "0" => None,
+                    _ => {
+                        let seconds = value.parse::<f64>().ok().filter(|s| s.is_finite() && *s > 0.0).ok_or_else(invalid)?;
+                        Some(Duration::from_secs_f64(seconds))
+                    }
+                }
+            }
+            "threads" => self.threads = value.parse().map_err(|_| invalid())?,
+            "deterministic" => self.deterministic = parse_switch(value).ok_or_else(invalid)?,
+            "dedupe-transposed" => self.dedupe_transposed = parse_switch(value).ok_or_else(invalid)?,
+            "peel-branching" => self.peel_branching = parse_positive(value).ok_or_else(invalid)?,
+            "peel-depth" => self.peel_depth = parse_positive(value).ok_or_else(invalid)?,
+            _ => return Err(ConfigError::UnknownSetting(setting.to_string())),
+        }
+        Ok(())
+    }
+}
+
+//same search the solver always did
+impl Default for SearchConfig {
+    fn default() -> SearchConfig {
+        SearchConfig {
+            branching: vec![7],
+            restart_branching: vec![3],
+            first_words: 3,
+            min_word_length: 5,
+            max_boards: 15,
+            time_budget: None,
+            threads: 0,
+            deterministic: false,
+            dedupe_transposed: false,
+            peel_branching: 3,
+            peel_depth: 5,
+        }
+    }
+}
+
+//comma separated branches per level, like 7,5,3
+fn parse_branching(value: &str) -> Option<Vec<usize>> {
+    value.split(',').map(|n| parse_positive(n.trim())).collect()
+}
+
+fn parse_positive(value: &str) -> Option<usize> {
+    value.parse::<usize>().ok().filter(|&n| n > 0)
+}
+
+fn parse_switch(value: &str) -> Option<bool> {
+    match value {
+        "on" | "true" => Some(true),
+        "off" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn switch(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+fn join(branching: &[usize]) -> String {
+    branching.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(",")
+}
+
+impl fmt::Display for SearchConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "branching: {}", join(&self.branching))?;
+        writeln!(f, "restart-branching: {}", join(&self.restart_branching))?;
+        writeln!(f, "first-words: {}", self.first_words)?;
+        writeln!(f, "min-word-length: {}", self.min_word_length)?;
+        writeln!(f, "max-boards: {}", self.max_boards)?;
+        match self.time_budget {
+            Some(budget) => writeln!(f, "time-budget: {}s", budget.as_secs_f64())?,
+            None => writeln!(f, "time-budget: none")?,
+        }
+        match self.threads {
+            0 => writeln!(f, "threads: every core")?,
+            n => writeln!(f, "threads: {}", n)?,
+        }
+        writeln!(f, "deterministic: {}", switch(self.deterministic))?;
+        writeln!(f, "dedupe-transposed: {}", switch(self.dedupe_transposed))?;
+        writeln!(f, "peel-branching: {}", self.peel_branching)?;
+        write!(f, "peel-depth: {}", self.peel_depth)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::UnknownSetting(setting) => write!(f, "there is no setting '{}', try one of {}", setting, SETTING_NAMES.join(", ")),
+            ConfigError::InvalidValue(setting, value) => write!(f, "'{}' is not a valid value for {}", value, setting),
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -0,0 +1,369 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+use crate::lexicon::{Rules, Verdict};
+use crate::source::{DictionaryError, WordSource};
+use crate::tiles::Tiles;
+
+//word list loaded into memory, the only place words of any length are checked against
+//house rules are kept apart from the word list so they can be saved and changed while playing
+#[derive(Clone)]
+pub struct Dictionary {
+    words: HashSet<String>, //every word in the word list that can be played
+    rejected: HashMap<String, Verdict>, //words in the word list that break one of the rules
+    allowed: BTreeSet<String>, //house rule words that can be played even though the word list doesn't allow them
+    denied: BTreeSet<String>, //house rule words that can't be played whatever the word list says
+    //playable words bucketed by letter counts so anagrams share one bucket
+    //buckets keep the order words first appear in the word list
+    buckets: Vec<(Tiles, Vec<String>)>,
+    bucket_index: HashMap<Tiles, usize>, //bucket of each letter count
+    pub(crate) trie: Trie, //every word that can be played
+    letter_words: [u32; 26], //number of words that can be played using each letter a-z
+    tile_words: u32, //number of words that can be played
+}
+
+//prefix tree of words, node 0 is the root
+#[derive(Clone)]
+pub(crate) struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Clone)]
+struct TrieNode {
+    children: [u32; 26], //index of the child node for each letter a-z, 0 if there is none
+    terminal: bool, //a word ends at this node
+}
+
+//how to order candidate words of the same length
+#[derive(Clone, Copy, PartialEq)]
+pub enum TieBreak {
+    Alphabetical, //earlier in the alphabet first
+    Rarity, //higher total letter rarity first, then alphabetical
+}
+
+//rarity of each letter a-z (scrabble tile values)
+static LETTER_RARITY: [u32; 26] = [1, 3, 3, 2, 1, 4, 2, 4, 1, 8, 5, 1, 3, 1, 1, 3, 10, 1, 1, 1, 1, 4, 4, 8, 4, 10];
+
+impl Dictionary {
+    pub fn load(words: &dyn WordSource, two_letter: Option<&dyn WordSource>, rules: &Rules) -> Result<Dictionary, DictionaryError> {
+        let two_letter = match two_letter {
+            Some(source) => Some(source.words()?),
+            None => None,
+        };
+        Ok(Dictionary::from_words(words.words()?, two_letter, rules))
+    }
+
+    //words that break one of the rules can't be played, they are remembered so why can explain it
+    //when two_letter is given it replaces the two letter words of the word list
+    pub fn from_words(mut list: Vec<String>, two_letter: Option<Vec<String>>, rules: &Rules) -> Dictionary {
+        let two_letter_list: Option<HashSet<String>> = two_letter.map(|two_letter| {
+            list.extend(two_letter.iter().cloned());
+            two_letter.iter().map(|w| w.to_lowercase()).collect()
+        });
+
+        let mut dictionary = Dictionary {
+            words: HashSet::new(),
+            rejected: HashMap::new(),
+            allowed: BTreeSet::new(),
+            denied: BTreeSet::new(),
+            buckets: Vec::new(),
+            bucket_index: HashMap::new(),
+            trie: Trie::new(),
+            letter_words: [0; 26],
+            tile_words: 0,
+        };
+
+        for entry in list {
+            let word = entry.to_lowercase();
+            let mut verdict = rules.check(&entry);
+            if verdict == Verdict::Allowed && word.len() == 2 && two_letter_list.as_ref().is_some_and(|list| !list.contains(&word)) {
+                verdict = Verdict::NotTwoLetterRule;
+            }
+            if verdict != Verdict::Allowed {
+                if !dictionary.words.contains(&word) {
+                    dictionary.rejected.entry(word).or_insert(verdict);
+                }
+                continue;
+            }
+            //a word can be listed both as a proper noun and as a plain word, like March and march
+            dictionary.rejected.remove(&word);
+            if dictionary.words.insert(word.clone()) {
+                dictionary.index(word);
+            }
+        }
+        dictionary
+    }
+
+    //make the word playable, it has to only use letters a-z
+    //returns false if it can't be a word
+    pub fn allow(&mut self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        if Tiles::from_letters(&word).is_err() || word.is_empty() {
+            return false;
+        }
+        let was_word = self.is_word(&word);
+        self.denied.remove(&word);
+        if !self.words.contains(&word) {
+            self.allowed.insert(word.clone());
+        }
+        if !was_word {
+            self.index(word);
+        }
+        true
+    }
+
+    //stop the word from being played, even if the word list has it
+    pub fn deny(&mut self, word: &str) {
+        let word = word.to_lowercase();
+        if self.is_word(&word) {
+            self.unindex(&word);
+        }
+        self.allowed.remove(&word);
+        self.denied.insert(word);
+    }
+
+    //house rule words in alphabetical order, for saving them
+    pub fn allowed(&self) -> Vec<String> {
+        self.allowed.iter().cloned().collect()
+    }
+
+    pub fn denied(&self) -> Vec<String> {
+        self.denied.iter().cloned().collect()
+    }
+
+    //add a playable word to the trie, the anagram buckets and the letter counts
+    fn index(&mut self, word: String) {
+        let counts = Tiles::from_letters(&word).expect("playable words only use letters a-z");
+        self.trie.insert(&word);
+        self.tile_words += 1;
+        for (i, &n) in counts.counts().iter().enumerate() {
+            if n > 0 {
+                self.letter_words[i] += 1;
+            }
+        }
+        match self.bucket_index.get(&counts) {
+            Some(&i) => self.buckets[i].1.push(word),
+            None => {
+                self.bucket_index.insert(counts, self.buckets.len());
+                self.buckets.push((counts, vec![word]));
+            }
+        }
+    }
+
+    fn unindex(&mut self, word: &str) {
+        let counts = Tiles::from_letters(word).expect("playable words only use letters a-z");
+        self.trie.remove(word);
+        self.tile_words -= 1;
+        for (i, &n) in counts.counts().iter().enumerate() {
+            if n > 0 {
+                self.letter_words[i] -= 1;
+            }
+        }
+        if let Some(&i) = self.bucket_index.get(&counts) {
+            self.buckets[i].1.retain(|w| w != word);
+        }
+    }
+
+    //whether the word can be played and if not which rule keeps it out
+    pub fn why(&self, word: &str) -> Verdict {
+        let word = word.to_lowercase();
+        if self.denied.contains(&word) {
+            Verdict::Denied
+        }
+        else if self.allowed.contains(&word) {
+            Verdict::HouseAllowed
+        }
+        else if self.words.contains(&word) {
+            Verdict::Allowed
+        }
+        else {
+            self.rejected.get(&word).copied().unwrap_or(Verdict::Unknown)
+        }
+    }
+
+    pub fn is_word(&self, word: &str) -> bool {
+        if self.denied.contains(word) {
+            return false;
+        }
+        self.words.contains(word) || self.allowed.contains(word)
+    }
+
+    //share of words that use the letter, low for letters that are hard to place
+    pub fn word_share(&self, c: char) -> f64 {
+        if !c.is_ascii_lowercase() || self.tile_words == 0 {
+            return 0.0;
+        }
+        self.letter_words[(c as u8 - b'a') as usize] as f64 / self.tile_words as f64
+    }
+
+    //all words that can be made from the tiles, in word list order within each anagram bucket
+    pub fn formable(&self, tiles: &Tiles) -> Vec<&str> {
+        let mut formable = Vec::new();
+        for (counts, words) in &self.buckets {
+            if counts.is_subset(tiles) {
+                formable.extend(words.iter().map(|w| w.as_str()));
+            }
+        }
+        formable
+    }
+}
+
+impl Trie {
+    pub(crate) fn new() -> Trie {
+        Trie {
+            nodes: vec![TrieNode::new()],
+        }
+    }
+
+    //word must only contain letters a-z
+    pub(crate) fn insert(&mut self, word: &str) {
+        let mut node = 0;
+        for c in word.bytes() {
+            let i = (c - b'a') as usize;
+            if self.nodes[node].children[i] == 0 {
+                self.nodes[node].children[i] = self.nodes.len() as u32;
+                self.nodes.push(TrieNode::new());
+            }
+            node = self.nodes[node].children[i] as usize;
+        }
+        self.nodes[node].terminal = true;
+    }
+
+    //nodes are left in place, the word just no longer ends there
+    pub(crate) fn remove(&mut self, word: &str) {
+        let mut node = 0;
+        for c in word.chars() {
+            match self.child(node, c) {
+                Some(child) => node = child,
+                None => return,
+            }
+        }
+        self.nodes[node].terminal = false;
+    }
+
+    pub(crate) fn child(&self, node: usize, c: char) -> Option<usize> {
+        if !c.is_ascii_lowercase() {
+            return None;
+        }
+        match self.nodes[node].children[(c as u8 - b'a') as usize] {
+            0 => None,
+            child => Some(child as usize),
+        }
+    }
+
+    pub(crate) fn is_terminal(&self, node: usize) -> bool {
+        self.nodes[node].terminal
+    }
+}
+
+impl TrieNode {
+    fn new() -> TrieNode {
+        TrieNode {
+            children: [0; 26],
+            terminal: false,
+        }
+    }
+}
+
+pub fn letter_rarity(c: char) -> u32 {
+    if c.is_ascii_lowercase() {
+        LETTER_RARITY[(c as u8 - b'a') as usize]
+    }
+    else {
+        0
+    }
+}
+
+pub fn word_rarity(word: &str) -> u32 {
+    word.bytes().filter(|c| c.is_ascii_lowercase()).map(|c| LETTER_RARITY[(c - b'a') as usize]).sum()
+}
+
+//ordering key for candidate words, larger is better
+//the word itself is the final tie break so the order is always deterministic
+pub fn word_rank(word: &str, tie_break: TieBreak) -> (usize, u32, Reverse<&str>) {
+    let secondary = match tie_break {
+        TieBreak::Alphabetical => 0,
+        TieBreak::Rarity => word_rarity(word),
+    };
+    (word.len(), secondary, Reverse(word))
+}
+
+//the num longest words that can be made from tiles, best first
+pub fn find_longest_words(dictionary: &Dictionary, tiles: &Tiles, num: usize, tie_break: TieBreak) -> Vec<String> {
+    if num == 0 {
+        return Vec::new();
+    }
+
+    //min heap holding the best num words seen so far
+    let mut heap = BinaryHeap::with_capacity(num + 1);
+    for word in dictionary.formable(tiles) {
+        heap.push(Reverse(word_rank(word, tie_break)));
+        if heap.len() > num {
+            heap.pop();
+        }
+    }
+
+    //sorted vec of Reverse is worst to best reversed, so best first
+    heap.into_sorted_vec().into_iter().map(|Reverse((_, _, Reverse(word)))| word.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{default_two_letter, PlainList};
+    use std::path::Path;
+
+    fn dictionary() -> Dictionary {
+        let words = ["at", "cat", "act", "tax", "taxi", "axis", "sit", "its", "cast", "zzz"];
+        Dictionary::from_words(words.iter().map(|w| w.to_string()).collect(), None, &Rules::default())
+    }
+
+    fn longest(dictionary: &Dictionary, rack: &str, num: usize, tie_break: TieBreak) -> Vec<String> {
+        find_longest_words(dictionary, &Tiles::from_letters(rack).unwrap(), num, tie_break)
+    }
+
+    #[test]
+    fn longest_words_alphabetical() {
+        let dictionary = dictionary();
+        assert_eq!(longest(&dictionary, "acitx", 3, TieBreak::Alphabetical), ["taxi", "act", "cat"]);
+    }
+
+    #[test]
+    fn longest_words_by_rarity() {
+        //x is worth more than c so tax comes before act and cat
+        let dictionary = dictionary();
+        assert_eq!(longest(&dictionary, "acitx", 3, TieBreak::Rarity), ["taxi", "tax", "act"]);
+    }
+
+    #[test]
+    fn longest_words_only_uses_the_rack() {
+        let dictionary = dictionary();
+        assert_eq!(longest(&dictionary, "acitx", 10, TieBreak::Alphabetical), ["taxi", "act", "cat", "tax", "at"]);
+        assert_eq!(longest(&dictionary, "aacst", 10, TieBreak::Alphabetical), ["cast", "act", "cat", "at"]);
+        assert!(longest(&dictionary, "qqq", 10, TieBreak::Alphabetical).is_empty());
+    }
+
+    #[test]
+    fn longest_words_none_asked_for() {
+        let dictionary = dictionary();
+        assert!(longest(&dictionary, "acitx", 0, TieBreak::Alphabetical).is_empty());
+        assert!(longest(&dictionary, "acitx", 0, TieBreak::Rarity).is_empty());
+    }
+
+    #[test]
+    fn shipped_two_letter_list_is_loaded_by_default() {
+        //qc and tv are in words.txt but are not words
+        let list = Path::new(env!("CARGO_MANIFEST_DIR")).join("words.txt");
+        let two_letter = default_two_letter(&list).expect("two letter list next to words.txt");
+        let dictionary = Dictionary::load(&PlainList { path: list }, Some(two_letter.as_ref()), &Rules::default()).unwrap();
+        assert!(!dictionary.is_word("qc"));
+        assert!(!dictionary.is_word("tv"));
+        assert_eq!(dictionary.why("qc"), Verdict::NotTwoLetterRule);
+        assert!(dictionary.is_word("qi"));
+        assert!(dictionary.is_word("za"));
+        assert!(dictionary.is_word("quiz"));
+    }
+}
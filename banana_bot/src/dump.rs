@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::board::Board;
+use crate::dictionary::{letter_rarity, Dictionary};
+use crate::movegen::generate_moves;
+
+//how much a tile in hand is worth trading in, higher is a better dump
+pub struct DumpAdvice {
+    pub letter: char,
+    pub score: f64,
+    pub in_hand: u8, //copies of the letter among the remaining tiles
+    pub placements: usize, //places on the board the letter fits using the rest of the hand
+    pub word_share: f64, //share of dictionary words that use the letter
+    pub rarity: u32, //scrabble value of the letter
+}
+
+//rank every distinct remaining tile of the board as a candidate for a dump
+//a tile scores high when few dictionary words use it, it is worth a lot in scrabble
+//and there are few or no places on the board where it can go right now
+pub fn dump_advice(dictionary: &Dictionary, b: &Board) -> Vec<DumpAdvice> {
+    let moves = if b.grid.is_empty() { Vec::new() } else { generate_moves(dictionary, &b.grid, &b.remaining) };
+
+    let mut advice = Vec::new();
+    for (i, &in_hand) in b.remaining.counts().iter().enumerate() {
+        if in_hand == 0 {
+            continue;
+        }
+        let letter = (b'a' + i as u8) as char;
+
+        let placements = moves.iter().filter(|m| m.used.count(letter) > 0).count();
+        let word_share = dictionary.word_share(letter);
+        let rarity = letter_rarity(letter);
+
+        let score = (1.0 - word_share) * 10.0 + rarity as f64 + 10.0 / (1.0 + placements as f64);
+        advice.push(DumpAdvice {
+            letter,
+            score,
+            in_hand,
+            placements,
+            word_share,
+            rarity,
+        });
+    }
+
+    advice.sort_by(|a1, a2| a2.score.partial_cmp(&a1.score).unwrap_or(Ordering::Equal).then(a1.letter.cmp(&a2.letter)));
+    advice
+}
+
+impl fmt::Display for DumpAdvice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (score {:.1}): in {:.1}% of words, worth {} in scrabble, ", self.letter, self.score, self.word_share * 100.0, self.rarity)?;
+        match self.placements {
+            0 => write!(f, "no place on the board")?,
+            1 => write!(f, "1 place on the board")?,
+            n => write!(f, "{} places on the board", n)?,
+        }
+        if self.in_hand > 1 {
+            write!(f, ", {} in hand", self.in_hand)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use std::fmt;
+
+//which words of a list can be played, used when a raw list is built and when the dictionary is loaded
+pub struct Rules {
+    pub min_length: usize,
+    pub max_length: usize, //a binary word list can't hold words longer than 255 letters
+    pub blocklist: HashSet<String>, //words that are never allowed, like profanity
+    pub proper_nouns: HashSet<String>, //names that are lowercase in the list
+}
+
+//why a word can or can't be played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    Unknown, //not in the word list
+    ProperNoun, //capitalized in the word list or on the proper noun list
+    NotLetters, //letters outside a-z like accents, no tile for them
+    TooShort(usize), //minimum length
+    TooLong(usize), //maximum length
+    Blocked,
+    NotTwoLetterRule, //two letter word that is not on the two letter list
+    HouseAllowed, //on the allow list
+    Denied, //on the deny list
+}
+
+//what happened to the entries of the raw list
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct BuildReport {
+    pub kept: usize,
+    pub proper_nouns: usize,
+    pub not_letters: usize,
+    pub too_short: usize,
+    pub too_long: usize,
+    pub blocked: usize,
+    pub duplicates: usize,
+}
+
+impl Rules {
+    //entry as it is written in the list, capitals mark proper nouns
+    pub fn check(&self, entry: &str) -> Verdict {
+        if entry.starts_with(char::is_uppercase) {
+            return Verdict::ProperNoun;
+        }
+        let word = entry.to_lowercase();
+        if !word.chars().all(|c| c.is_ascii_lowercase()) {
+            Verdict::NotLetters
+        }
+        else if self.proper_nouns.contains(&word) {
+            Verdict::ProperNoun
+        }
+        else if word.len() < self.min_length {
+            Verdict::TooShort(self.min_length)
+        }
+        else if word.len() > self.max_length {
+            Verdict::TooLong(self.max_length)
+        }
+        else if self.blocklist.contains(&word) {
+            Verdict::Blocked
+        }
+        else {
+            Verdict::Allowed
+        }
+    }
+}
+
+//single letters are not words
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            min_length: 2,
+            max_length: 255,
+            blocklist: HashSet::new(),
+            proper_nouns: HashSet::new(),
+        }
+    }
+}
+
+//normalized words in alphabetical order, the same raw list always gives the same result
+//entries are stripped of anything that is not a letter and lowered,
+//so "Paris" is dropped and "rock-n-roll" becomes "rocknroll"
+pub fn build(raw: &[String], rules: &Rules) -> (Vec<String>, BuildReport) {
+    let mut report = BuildReport::default();
+    let mut seen = HashSet::new();
+    let mut words = Vec::new();
+
+    for entry in raw {
+        let entry: String = entry.chars().filter(|c| c.is_alphabetic()).collect();
+        if entry.is_empty() {
+            continue;
+        }
+
+        match rules.check(&entry) {
+            Verdict::Allowed => {
+                let word = entry.to_lowercase();
+                if seen.insert(word.clone()) {
+                    words.push(word);
+                }
+                else {
+                    report.duplicates += 1;
+                }
+            }
+            Verdict::ProperNoun => report.proper_nouns += 1,
+            Verdict::NotLetters => report.not_letters += 1,
+            Verdict::TooShort(_) => report.too_short += 1,
+            Verdict::TooLong(_) => report.too_long += 1,
+            Verdict::Blocked => report.blocked += 1,
+            Verdict::Unknown | Verdict::NotTwoLetterRule | Verdict::HouseAllowed | Verdict::Denied => {}
+        }
+    }
+
+    words.sort_unstable();
+    report.kept = words.len();
+    (words, report)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Allowed => write!(f, "it is in the word list"),
+            Verdict::Unknown => write!(f, "it is not in the word list"),
+            Verdict::ProperNoun => write!(f, "it is a proper noun"),
+            Verdict::NotLetters => write!(f, "it has letters that are not tiles"),
+            Verdict::TooShort(min) => write!(f, "it is shorter than {} letters", min),
+            Verdict::TooLong(max) => write!(f, "it is longer than {} letters", max),
+            Verdict::Blocked => write!(f, "it is on the blocklist"),
+            Verdict::NotTwoLetterRule => write!(f, "it is not on the two letter list"),
+            Verdict::HouseAllowed => write!(f, "it is on the house rule allow list"),
+            Verdict::Denied => write!(f, "it is on the house rule deny list"),
+        }
+    }
+}
+
+impl fmt::Display for BuildReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "kept {} words", self.kept)?;
+        writeln!(f, "dropped {} proper nouns", self.proper_nouns)?;
+        writeln!(f, "dropped {} words with letters outside a-z", self.not_letters)?;
+        writeln!(f, "dropped {} words that are too short", self.too_short)?;
+        writeln!(f, "dropped {} words that are too long", self.too_long)?;
+        writeln!(f, "dropped {} blocked words", self.blocked)?;
+        write!(f, "dropped {} duplicates", self.duplicates)
+    }
+}
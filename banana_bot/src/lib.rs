@@ -0,0 +1,12 @@
+pub mod board;
+pub mod bunch;
+pub mod config;
+pub mod dictionary;
+pub mod dump;
+pub mod lexicon;
+pub mod movegen;
+pub mod score;
+pub mod search;
+pub mod solver;
+pub mod source;
+pub mod tiles;
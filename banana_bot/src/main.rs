@@ -1,755 +1,508 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
 use std::io;
-use std::fs::File;
-use std::io::BufReader;
-use std::io::BufRead;
-use lazy_static::lazy_static;
-use std::sync::Mutex;
-
-struct Board {
-    tiles: String, //all tiles currently in board
-    remaining: String, //not linked
-    bottom_end: char, //can be last letter of next word
-    top_end: char, //can be first letter of next word
-    top_end_vert: bool, //true if top end is vertical -- future use
-    bottom_end_vert: bool, //true if bottom end is vertical -- future use
-    words: Vec<String>, //linked words
-    //locations of sarting letter of words plus their direction
-    //(0,0) is top left corner
-    word_positions: Vec<(i32, i32, i32)>, //positions of words ex. [1,5,1] means word starts at position 1,5 and is vertical
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+
+use banana_bot::board::Board;
+use banana_bot::config::{SearchConfig, SETTING_NAMES};
+use banana_bot::dictionary::{Dictionary, TieBreak};
+use banana_bot::lexicon::{build, Rules, Verdict};
+use banana_bot::score::{scorer_by_name, SCORER_NAMES};
+use banana_bot::solver::Game;
+use banana_bot::source::{companion_path, default_two_letter, source_for_path, write_word_list, DictionaryError, TWO_LETTER_LIST};
+
+//environment variables with the paths of the word lists
+static DICT_VAR: &str = "BANANA_BOT_DICT";
+static TWO_LETTER_VAR: &str = "BANANA_BOT_TWO_LETTER";
+
+//lists of words that can't be played, read from the lists that go with the word list unless their flag is passed
+//they only fit the word list they were written for, words.proper_nouns.txt drops the names words.txt has in lowercase
+static PROPER_NOUNS_LIST: &str = "proper_nouns";
+static BLOCKLIST: &str = "blocklist";
+
+//files the allow and deny lists are saved to after every change
+struct HouseRules {
+    allow: PathBuf,
+    deny: PathBuf,
 }
 
-//Number of branches to search
-static DEEP_DEPTH: i32 = 7;
-static SHALLOW_DEPTH: i32 = 3;
-
-//All letters user has logged so far
-lazy_static! {
-    static ref MASTER_LETTERS: Mutex<String> = Mutex::new(String::new());
-}
-
-//Currently solved boards
-lazy_static! {
-    static ref BOARDS: Mutex<Vec<Board>> = Mutex::new(Vec::new());
-}
-
-fn add_letters() {
+fn add_letters(game: &mut Game) {
     //read user input
     let mut letters = String::new();
     println!("Enter letters: ");
     io::stdin().read_line(&mut letters).expect("Failed to read line");
-    let letters = letters.trim();
-    MASTER_LETTERS.lock().unwrap().push_str(letters);
+    match game.add_letters(&letters) {
+        Ok(tiles) => println!("Added: {}", tiles),
+        Err(e) => println!("Nothing was added: {}", e),
+    }
 }
 
-fn print_master_letters() {
-    println!("Master Letters: {}", MASTER_LETTERS.lock().unwrap());
+fn print_master_letters(game: &Game) {
+    println!("Master Letters: {}", game.letters());
 }
 
-fn get_master_letters() -> String {
-    MASTER_LETTERS.lock().unwrap().to_string()
+fn toggle_tie_break(game: &mut Game) {
+    if game.tie_break() == TieBreak::Rarity {
+        game.set_tie_break(TieBreak::Alphabetical);
+        println!("Words of the same length are now ordered alphabetically.");
+    }
+    else {
+        game.set_tie_break(TieBreak::Rarity);
+        println!("Words of the same length are now ordered by letter rarity.");
+    }
 }
 
-fn reset_master_letters() {
-    MASTER_LETTERS.lock().unwrap().clear();
-    BOARDS.lock().unwrap().clear();
-}
+fn solve(game: &mut Game) {
+    for invalid in game.solve() {
+        let words: Vec<String> = invalid.iter().map(|w| w.to_string()).collect();
+        println!("Dropped board with invalid words: {}", words.join(", "));
+    }
+    println!("Search {}", game.stats());
 
-fn reset_boards() {
-    BOARDS.lock().unwrap().clear();
-}
+    println!("-------------------------------------------------------");
 
-fn copy_and_push(b: &Board) {
-    let b_copy = Board {
-        tiles: b.tiles.clone(),
-        remaining: b.remaining.clone(),
-        bottom_end: b.bottom_end,
-        top_end: b.top_end,
-        words: b.words.clone(),
-        top_end_vert: b.top_end_vert,
-        bottom_end_vert: b.bottom_end_vert,
-        word_positions: b.word_positions.clone(),
-    };
-    BOARDS.lock().unwrap().push(b_copy);
+    pretty_print_boards(game);
 }
 
-//recursively construct word and then find word with remaining letters
-//paramaters: remaining letters, Board struct
-//if no more words can be found that connect or no more remaining letters, add board to boards
-fn construct(b: &Board, letters: &str, depth : i32) {
-
-    //if not correct amout of letters, return
-    //should never satisfy this if statement
-    if b.tiles.len() != MASTER_LETTERS.lock().unwrap().len() {
-        return;
-    }
-
-    //if no more letters, add board to boards
-    if letters.len() == 0 {
-        copy_and_push(&b);
-        return;
-    }
+fn help() {
+    println!("Welcome to banana_bot!");
+    println!("Enter 'q' to quit.");
+    println!("Enter 'p' to print the current letters.");
+    println!("Enter 'r' to reset the current letters.");
+    println!("Enter 'b' to reset the current boards.");
+    println!("Enter 's' to solve the current letters.");
+    println!("Enter 'h' to print this help message.");
+    println!("Enter 'c' to place remainting tiles given the current boards.");
+    println!("Enter 't' to toggle ordering same length words by rarity or alphabetically.");
+    println!("Enter 'k' to keep one of the boards as the one on the table.");
+    println!("Enter 'l' to peel: add letters to the board on the table.");
+    println!("Enter 'd' to see which tile is best to dump.");
+    println!("Enter 'o' to see the odds of each letter on the next peel.");
+    println!("Enter 'set' to see the search settings, or 'set <setting> <value>' to change one.");
+    println!("Enter 'why <word>' to see whether a word can be played.");
+    println!("Enter 'allow <word>' or 'deny <word>' to change the house rules.");
+    println!("Enter any other key to add letters to the current letters.");
+}
 
-    //if no words on board, add first word
-    if b.words.len() == 0 {
-        let l = find_longest_words(&letters, 3);
-        for word in &l {
-            let remaining = remove_from_tiles(&word, &letters);
-            let mut b2 = Board{
-                tiles: b.tiles.clone(),
-                remaining: remaining.clone(),
-                bottom_end: word.chars().next().unwrap(),
-                top_end: word.chars().last().unwrap(),
-                words: b.words.clone(),
-                bottom_end_vert: b.bottom_end_vert,
-                top_end_vert: b.top_end_vert,
-                word_positions: b.word_positions.clone(),
-            };
-            b2.words.push(word.to_string());
-            b2.word_positions.push((0,0,0));
-            construct(&b2, &remaining, depth);
-        }
-        return;
-    }
+fn init_game(game: &mut Game, house: &HouseRules) {
+    println!("Welcome to banana_bot!");
+    println!("Enter 'q' to quit.");
+    println!("Enter 'p' to print the current letters.");
+    println!("Enter 'r' to reset the current letters.");
+    println!("Enter 'b' to reset the current boards.");
+    println!("Enter 's' to solve the current letters.");
+    println!("Enter 'h' to print this help message.");
+    println!("Enter 'c' to place remainting tiles given the current boards.");
+    println!("Enter 't' to toggle ordering same length words by rarity or alphabetically.");
+    println!("Enter 'k' to keep one of the boards as the one on the table.");
+    println!("Enter 'l' to peel: add letters to the board on the table.");
+    println!("Enter 'd' to see which tile is best to dump.");
+    println!("Enter 'o' to see the odds of each letter on the next peel.");
+    println!("Enter 'set' to see the search settings, or 'set <setting> <value>' to change one.");
+    println!("Enter 'why <word>' to see whether a word can be played.");
+    println!("Enter 'allow <word>' or 'deny <word>' to change the house rules.");
 
-    //check if we can make words that link to current board -- if not add to boards
-    //otherwise recurse
-    let mut made_words = false;
+    println!("Enter any other key to add letters to the current letters.");
+    println!();
+    println!("Enter the letters you have to start the game.");
+    println!();
+    add_letters(game);
 
-    // //find longest words that can be made from remaining letters
-    let l = find_longest_words(&letters, depth.try_into().unwrap());
-    //see if these words can form two letter words with the current board
-    for word in &l {
+    loop {
+        println!();
+        let mut input = String::new();
+        println!("Enter command: ");
+        io::stdin().read_line(&mut input).expect("Failed to read line");
+        let input = input.trim();
 
-        //if word 4 letters or less, skip
-        if word.len() <= 4 {
+        if input == "set" || input.starts_with("set ") {
+            set(game, &input[3..]);
             continue;
         }
-
-        //the below struture is slightly redundant in terms of line count
-        //but it makes modifications easier in the future
-        
-        if b.top_end_vert {
-            let mut two_letter_word = b.top_end.to_string();
-            two_letter_word.push_str(&word.chars().last().unwrap().to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: b.bottom_end,
-                    top_end: word.chars().next().unwrap(),
-                    words: b.words.clone(),
-                    bottom_end_vert: b.bottom_end_vert,
-                    top_end_vert: true,
-                    word_positions: b.word_positions.clone(),
-                };
-                
-                b2.remaining = remaining.clone();
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[b.word_positions.len()-1].0 as i32;
-                let prev_y = b.word_positions[b.word_positions.len()-1].1 as i32;
-                //length of word
-                let len = word.len() as i32;
-                b2.word_positions.push((prev_x + 1, prev_y + 1 - len , 1)); //up = less y ((0,0) is top left)
-                b2.words.push(word.to_string());
-                construct(&b2, &remaining, depth);
-            }
-
-            two_letter_word = word.chars().last().unwrap().to_string();
-            two_letter_word.push_str(&b.top_end.to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: b.bottom_end,
-                    top_end: word.chars().next().unwrap(),
-                    words: b.words.clone(),
-                    bottom_end_vert: b.bottom_end_vert,
-                    top_end_vert: true,
-                    word_positions: b.word_positions.clone(),
-                };
-                
-                b2.remaining = remaining.clone();
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[b.word_positions.len()-1].0 as i32;
-                let prev_y = b.word_positions[b.word_positions.len()-1].1 as i32;
-                //length of word
-                let len = word.len() as i32;
-                b2.word_positions.push((prev_x - 1, prev_y + 1 - len , 1)); //up = less y ((0,0) is top left)
-                b2.words.push(word.to_string());
-                construct(&b2, &remaining, depth);
-            }
-            
+        if let Some(word) = input.strip_prefix("why ") {
+            why(game, word.trim());
+            continue;
         }
-        else {
-            let mut two_letter_word = b.top_end.to_string();
-            two_letter_word.push_str(&word.chars().next().unwrap().to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: b.bottom_end,
-                    top_end: word.chars().last().unwrap(),
-                    words: b.words.clone(),
-                    bottom_end_vert: b.bottom_end_vert,
-                    top_end_vert: false,
-                    word_positions: b.word_positions.clone(),
-                };
-                
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = b.words[b.words.len()-1].len() as i32;
-                //adding to top end
-                //this is a horizontal word so the third coordinate is 0
-                let prev_x = b.word_positions[b.word_positions.len()-1].0 + len - 1 as i32;
-                let prev_y = b.word_positions[b.word_positions.len()-1].1 as i32;
-                
-                b2.word_positions.push((prev_x, prev_y + 1 , 0)); //up = less y ((0,0) is top left)
-                b2.words.push(word.to_string());
-                construct(&b2, &remaining, depth);
-            }
-
-            two_letter_word = word.chars().next().unwrap().to_string();
-            two_letter_word.push_str(&b.top_end.to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: b.bottom_end,
-                    top_end: word.chars().last().unwrap(),
-                    words: b.words.clone(),
-                    bottom_end_vert: b.bottom_end_vert,
-                    top_end_vert: false,
-                    word_positions: b.word_positions.clone(),
-                };
-                
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = b.words[b.words.len()-1].len() as i32;
-                //adding to top end
-                //this is a horizontal word so the third coordinate is 0
-                let prev_x = b.word_positions[b.word_positions.len()-1].0 + len - 1 as i32;
-                let prev_y = b.word_positions[b.word_positions.len()-1].1 as i32;
-                
-                b2.word_positions.push((prev_x, prev_y - 1 , 0)); //up = less y ((0,0) is top left)
-                b2.words.push(word.to_string());
-
-                construct(&b2, &remaining, depth);
-            }
+        if let Some(word) = input.strip_prefix("allow ") {
+            allow(game, house, word.trim());
+            continue;
         }
-
-        if b.bottom_end_vert {
-            let mut two_letter_word = b.bottom_end.to_string();
-            two_letter_word.push_str(&word.chars().next().unwrap().to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: word.chars().last().unwrap(),
-                    top_end: b.top_end,
-                    words: b.words.clone(),
-                    bottom_end_vert: true,
-                    top_end_vert: b.top_end_vert,
-                    word_positions: b.word_positions.clone(),
-                };
-                
-                //add word to beginning of words
-                b2.words.insert(0, word.to_string());
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = word.len() as i32;
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[0].0 as i32;
-                let prev_y = b.word_positions[0].1 + len - 1 as i32;
-                
-                b2.word_positions.insert(0, (prev_x + 1, prev_y , 1)); //up = less y ((0,0) is top left)
-                
-                construct(&b2, &remaining, depth);
-            }
-
-            two_letter_word = word.chars().next().unwrap().to_string();
-            two_letter_word.push_str(&b.bottom_end.to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: word.chars().last().unwrap(),
-                    top_end: b.top_end,
-                    words: b.words.clone(),
-                    bottom_end_vert: true,
-                    top_end_vert: b.top_end_vert,
-                    word_positions: b.word_positions.clone(),
-                };
-                //add word to beginning of words
-                b2.words.insert(0, word.to_string());
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = word.len() as i32;
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[0].0 as i32;
-                let prev_y = b.word_positions[0].1 + len - 1 as i32;
-                
-                b2.word_positions.insert(0, (prev_x - 1, prev_y , 1)); //up = less y ((0,0) is top left)
-                
-                construct(&b2, &remaining, depth);
-            }
-            
+        if let Some(word) = input.strip_prefix("deny ") {
+            deny(game, house, word.trim());
+            continue;
         }
-        else {
-            let mut two_letter_word = b.bottom_end.to_string();
-            two_letter_word.push_str(&word.chars().last().unwrap().to_string());
-    
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: word.chars().next().unwrap(),
-                    top_end: b.top_end,
-                    words: b.words.clone(),
-                    bottom_end_vert: false,
-                    top_end_vert: b.top_end_vert,
-                    word_positions: b.word_positions.clone(),
-                };
-                //add word to beginning of words
-                b2.words.insert(0, word.to_string());
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = word.len() as i32;
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[0].0 as i32;
-                let prev_y = b.word_positions[0].1 as i32;
-                
-                b2.word_positions.insert(0, (prev_x - len + 1, prev_y + 1, 0)); //up = less y ((0,0) is top left)
-                
-                construct(&b2, &remaining, depth);
-            }
 
-            two_letter_word = word.chars().last().unwrap().to_string();
-            two_letter_word.push_str(&b.bottom_end.to_string());
-
-            //if two letter word is in dictionary, add to board
-            if is_valid_two_letter(&two_letter_word) {
-                made_words = true;
-                let remaining = remove_from_tiles(&word, &letters);
-                let mut b2 = Board{
-                    tiles: b.tiles.clone(),
-                    remaining: remaining.clone(),
-                    bottom_end: word.chars().next().unwrap(),
-                    top_end: b.top_end,
-                    words: b.words.clone(),
-                    bottom_end_vert: false,
-                    top_end_vert: b.top_end_vert,
-                    word_positions: b.word_positions.clone(),
-                };
-                //add word to beginning of words
-                b2.words.insert(0, word.to_string());
-                b2.remaining = remaining.clone();
-                //length of word
-                let len = word.len() as i32;
-                //adding to top end
-                //this is a vertical word so the third coordinate is 1
-                let prev_x = b.word_positions[0].0 as i32;
-                let prev_y = b.word_positions[0].1 as i32;
-                
-                b2.word_positions.insert(0, (prev_x - len + 1, prev_y - 1, 0)); //up = less y ((0,0) is top left)
-                 
-                construct(&b2, &remaining, depth);
-            }
+        match input {
+            "q" => break,
+            "p" => print_master_letters(game),
+            "r" => game.reset(),
+            "s" => solve(game),
+            "h" => help(),
+            "b" => game.reset_boards(),
+            "c" => place_remaining(game),
+            "t" => toggle_tie_break(game),
+            "k" => keep_board(game),
+            "l" => peel(game),
+            "d" => dump(game),
+            "o" => print_odds(game),
+            _ => add_letters(game),
         }
     }
-
-    //if no words were made, add board to boards
-    if !made_words {
-        copy_and_push(&b);
-    }
-
 }
 
-fn solve() {
-
-    //check if there are any boards
-    if BOARDS.lock().unwrap().len() == 0 {
-        //create empty board
-        let b = Board {
-            tiles: get_master_letters(),
-            remaining: get_master_letters(),
-            bottom_end: ' ',
-            top_end: ' ',
-            words: Vec::new(),
-            bottom_end_vert: false,
-            top_end_vert: false,
-            word_positions: Vec::new(),
-        };
-        construct(&b, &get_master_letters(), DEEP_DEPTH);
+//print the search settings or change one, like "set mode speed" or "set branching 7,5,3"
+fn set(game: &mut Game, input: &str) {
+    let mut parts = input.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => println!("{}", game.config()),
+        (Some(setting), Some(value), None) => match game.config_mut().set(setting, value) {
+            Ok(()) => println!("{}", game.config()),
+            Err(e) => println!("Can't change the setting: {}", e),
+        },
+        _ => println!("Settings are changed with 'set <setting> <value>', settings are {}", SETTING_NAMES.join(", ")),
     }
-    else {
-        let mut recurse_boards = Vec::new();
-
-        //double for loop structure prevents concurrent modification
-        for board in BOARDS.lock().unwrap().iter_mut() {
-            let board_clone = Board {
-                tiles: board.tiles.clone(),
-                remaining: board.remaining.clone(),
-                bottom_end: board.bottom_end,
-                top_end: board.top_end,
-                words: board.words.clone(),
-                bottom_end_vert: board.bottom_end_vert,
-                top_end_vert: board.top_end_vert,
-                word_positions: board.word_positions.clone(),
-            };
-            recurse_boards.push(board_clone);
-        }
-
-        for mut board in recurse_boards {
-            let diff = remove_from_tiles(&board.tiles, &MASTER_LETTERS.lock().unwrap());
-            board.remaining.push_str(&diff);
-            board.tiles = MASTER_LETTERS.lock().unwrap().clone();
-            construct(&board, &board.remaining, DEEP_DEPTH);
-        }
+}
 
-        //Check refactor condition
-        //create empty board
-        let b = Board {
-            tiles: get_master_letters(),
-            remaining: get_master_letters(),
-            bottom_end: ' ',
-            top_end: ' ',
-            words: Vec::new(),
-            bottom_end_vert: false,
-            top_end_vert: false,
-            word_positions: Vec::new(),
-        };
-        construct(&b, &get_master_letters(), SHALLOW_DEPTH);
+fn why(game: &Game, word: &str) {
+    match game.dictionary().why(word) {
+        verdict @ (Verdict::Allowed | Verdict::HouseAllowed) => println!("'{}' can be played, {}", word, verdict),
+        verdict => println!("'{}' can't be played, {}", word, verdict),
     }
-
-    purge_old_boards();
-    //for boards with identical words in in slightly different configurations
-    remove_duplicates();
-
-    sort_boards();
-
-    println!("-------------------------------------------------------");
-
-    pretty_print_boards();
 }
 
-//sort BOARDS by remaining letters
-fn sort_boards() {
-    BOARDS.lock().unwrap().sort_by(|a, b| b.remaining.len().cmp(&a.remaining.len()));
-    BOARDS.lock().unwrap().truncate(15);
+fn allow(game: &mut Game, house: &HouseRules, word: &str) {
+    if !game.dictionary_mut().allow(word) {
+        println!("'{}' can't be a word, it has letters that are not tiles", word);
+        return;
+    }
+    println!("'{}' can be played", word);
+    save_house_rules(game, house);
 }
 
-fn purge_old_boards() {
-    //remove boards that have less letters than MASTER_LETTERS
-    let mut i = 0;
-    while i < BOARDS.lock().unwrap().len() {
-        if BOARDS.lock().unwrap()[i].tiles.len() < MASTER_LETTERS.lock().unwrap().len() {
-            BOARDS.lock().unwrap().remove(i);
-        }
-        else {
-            i += 1;
-        }
-    }
+fn deny(game: &mut Game, house: &HouseRules, word: &str) {
+    game.dictionary_mut().deny(word);
+    println!("'{}' can't be played", word);
+    save_house_rules(game, house);
 }
 
-//remove boards that have the same words
-fn remove_duplicates() {
-    //Since boards with the same words have the same remaining letters, we only need to check the words and can diregard the order
-    
-    //make copy of boards to avoid concurrent modification
-    let mut boards_copy = Vec::new();
-    for board in BOARDS.lock().unwrap().iter() {
-        let clone_board = Board {
-            tiles: board.tiles.clone(),
-            remaining: board.remaining.clone(),
-            bottom_end: board.bottom_end,
-            top_end: board.top_end,
-            words: board.words.clone(),
-            bottom_end_vert: board.bottom_end_vert,
-            top_end_vert: board.top_end_vert,
-            word_positions: board.word_positions.clone(),
-        };
-        boards_copy.push(clone_board);
-    }
-    //mark which boards to remove
-    let mut to_remove = Vec::new();
-    
-    let mut i = 0;
-    while i < boards_copy.len() {
-        let mut j = i + 1;
-        while j < boards_copy.len() {
-            if boards_copy[i].words == boards_copy[j].words {
-                to_remove.push(j);
+fn save_house_rules(game: &Game, house: &HouseRules) {
+    for path in [&house.allow, &house.deny] {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            if let Err(e) = fs::create_dir_all(dir) {
+                println!("The house rules were changed but not saved: can't create {}: {}", dir.display(), e);
+                return;
             }
-            j += 1;
         }
-        i += 1;
     }
+    let saved = write_word_list(&house.allow, &game.dictionary().allowed()).and_then(|()| write_word_list(&house.deny, &game.dictionary().denied()));
+    if let Err(e) = saved {
+        println!("The house rules were changed but not saved: {}", e);
+    }
+}
 
-    //sort to_remove from largest to smallest
-    to_remove.sort_by(|a, b| b.cmp(a));
+fn keep_board(game: &mut Game) {
+    let mut input = String::new();
+    println!("Enter board number: ");
+    io::stdin().read_line(&mut input).expect("Failed to read line");
 
-    //remove duplicates from to_remove
-    if to_remove.len() == 0 {
-        return;
-    }
-    let mut i = 0;
-    while i < to_remove.len() - 1 {
-        if to_remove[i] == to_remove[i + 1] {
-            to_remove.remove(i);
-        }
-        else {
-            i += 1;
+    let kept = match input.trim().parse::<usize>() {
+        Ok(n) if n > 0 => game.keep_board(n - 1),
+        _ => None,
+    };
+    match kept {
+        Some(board) => {
+            println!("Keeping this board on the table:");
+            pretty_print_board(board);
         }
+        None => println!("There is no board {}", input.trim()),
     }
-
-    //remove marked boards
-    for index in to_remove {
-        BOARDS.lock().unwrap().remove(index);
-    }
-
 }
 
-fn help() {
-    println!("Welcome to banana_bot!");
-    println!("Enter 'q' to quit.");
-    println!("Enter 'p' to print the current letters.");
-    println!("Enter 'r' to reset the current letters.");
-    println!("Enter 'b' to reset the current boards.");
-    println!("Enter 's' to solve the current letters.");
-    println!("Enter 'h' to print this help message.");
-    println!("Enter 'c' to place remainting tiles given the current boards.");
-    println!("Enter any other key to add letters to the current letters.");
-}
+fn peel(game: &mut Game) {
+    let mut letters = String::new();
+    println!("Enter peeled letters: ");
+    io::stdin().read_line(&mut letters).expect("Failed to read line");
 
-fn is_valid_two_letter(word: &str) -> bool {
-    let file = File::open("two_letter_words.txt").expect("Unable to open file");
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if line == word {
-                return true;
+    match game.peel(&letters) {
+        Ok(results) => {
+            println!("Search {}", game.stats());
+            println!("-------------------------------------------------------");
+            for (i, result) in results.iter().enumerate() {
+                println!("Board {}, tiles to move: {}", i + 1, result.moved);
+                pretty_print_board(&result.board);
             }
+            println!("Board 1 is now the one on the table, use 'k' if you laid out another one.");
         }
+        Err(e) => println!("Can't peel: {}", e),
     }
-    false
 }
 
-fn is_in_tiles(word: &str, tiles: &str) -> bool {
-    let mut tiles = tiles.to_string();
-    for c in word.chars() {
-        if let Some(i) = tiles.find(c) {
-            tiles.remove(i);
-        } else {
-            return false;
+fn dump(game: &mut Game) {
+    let advice = game.dump_advice();
+    //the advice is for the board on the table, or the best board if none is kept
+    match (game.table(), game.boards().first()) {
+        (Some(table), _) => {
+            println!("Board on the table:");
+            pretty_print_board(table);
         }
+        (None, Some(board)) => {
+            println!("No board is kept on the table, looking at board 1:");
+            pretty_print_board(board);
+        }
+        (None, None) => {}
     }
-    true
-}
-
-fn remove_from_tiles(word: &str, tiles: &str) -> String {
-    let mut tiles = tiles.to_string();
-    for c in word.chars() {
-        if let Some(i) = tiles.find(c) {
-            tiles.remove(i);
+    match advice {
+        Some(advice) if !advice.is_empty() => {
+            println!("Best tiles to dump, best first:");
+            for (i, a) in advice.iter().enumerate() {
+                println!("{}. {}", i + 1, a);
+            }
         }
+        Some(_) => println!("Every tile is already on the board."),
+        None => println!("Solve or keep a board first."),
     }
-    tiles
 }
 
-fn find_longest_words(tiles: &str, num: usize) -> Vec<String> {
-    let mut longest = Vec::new();
-    let file = File::open("words.txt").expect("Unable to open file");
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if is_in_tiles(&line, tiles) {
-                if longest.len() < num {
-                    longest.push(line);
-                } else {
-                    for i in 0..longest.len() {
-                        if line.len() > longest[i].len() {
-                            longest[i] = line;
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+//chance of each letter being the next tile we draw, most likely first
+fn print_odds(game: &Game) {
+    let bunch = game.bunch();
+    println!("{} tiles not seen yet", bunch.unseen().len());
+
+    let mut odds: Vec<(char, f64)> = bunch.probabilities().iter().enumerate()
+        .map(|(i, &p)| ((b'a' + i as u8) as char, p))
+        .filter(|&(_, p)| p > 0.0)
+        .collect();
+    odds.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (c, p) in odds {
+        println!("{}: {:.1}%", c, p * 100.0);
     }
-    longest
 }
 
+//attach leftover tiles to the current boards and show them
+fn place_remaining(game: &mut Game) {
+    game.place_remaining();
+    println!("Search {}", game.stats());
 
-fn init_game() {
-    println!("Welcome to banana_bot!");
-    println!("Enter 'q' to quit.");
-    println!("Enter 'p' to print the current letters.");
-    println!("Enter 'r' to reset the current letters.");
-    println!("Enter 'b' to reset the current boards.");
-    println!("Enter 's' to solve the current letters.");
-    println!("Enter 'h' to print this help message.");
-    println!("Enter 'c' to place remainting tiles given the current boards.");
+    println!("-------------------------------------------------------");
 
-    println!("Enter any other key to add letters to the current letters.");
-    println!("");
-    println!("Enter the letters you have to start the game.");
-    println!("");
-    add_letters();
+    for (i, board) in game.boards().iter().enumerate() {
+        println!("Board {}", i + 1);
+        pretty_print_board(board);
+    }
+}
 
-    loop {
-        println!("");
-        let mut input = String::new();
-        println!("Enter command: ");
-        io::stdin().read_line(&mut input).expect("Failed to read line");
-        let input = input.trim();
+fn pretty_print_board(b: &Board) {
+    println!("{}", b);
+    println!("--------------------");
+}
 
-        match input {
-            "q" => break,
-            "p" => print_master_letters(),
-            "r" => reset_master_letters(),
-            "s" => solve(),
-            "h" => help(),
-            "b" => reset_boards(),
-            "c" => pretty_print_boards(),
-            _ => add_letters(),
-        }
+fn pretty_print_boards(game: &mut Game) {
+    game.sync_boards();
+
+    for (i, board) in game.boards().iter().enumerate() {
+        println!("Board {}", i + 1);
+        pretty_print_board(board);
     }
 }
 
-fn get_word_coordinates(word: &str, position_x: i32, position_y: i32, position_vert: i32 , x_offset: i32, y_offset: i32) -> Vec<(i32, i32)> {
-    let mut coordinates = Vec::new();
-    //determine if the word is vertical or horizontal
-    if position_vert == 1 {
-        //word is vertical
-        for i in 0..word.len() {
-            coordinates.push((position_x + x_offset, position_y + y_offset + i as i32));
-        }
-    }
-    else {
-        //word is horizontal
-        for i in 0..word.len() {
-            coordinates.push((position_x + x_offset + i as i32, position_y + y_offset));
+fn usage() -> ! {
+    println!("Usage: banana_bot dict build <raw list> <output> [--min-length <n>] [--max-length <n>] [--blocklist <path>] [--proper-nouns <path>]");
+    println!("       banana_bot [--dict <path>] [--two-letter <path>] [--allow <path>] [--deny <path>] [--scorer {}] [--<rule> <value>]... [--<setting> <value>]...", SCORER_NAMES.join("|"));
+    println!("Word list rules: --min-length <n>, --max-length <n>, --blocklist <path>, --proper-nouns <path>");
+    println!("Without those flags <list>.{}.txt and <list>.{}.txt next to the word list, or the raw list for dict build, are used if they exist.", BLOCKLIST, PROPER_NOUNS_LIST);
+    println!("Word lists can be plain text, gzip (.gz) or a binary word list (.bin).");
+    println!("A two letter list replaces the two letter words of the dictionary, <list>.{}.txt next to the word list is used if it exists.", TWO_LETTER_LIST);
+    println!("They can also be set with {} and {}.", DICT_VAR, TWO_LETTER_VAR);
+    println!("Allow and deny lists are house rules kept in {}, they are saved when changed with 'allow' and 'deny'.", data_dir().display());
+    println!("Search settings: {}", SETTING_NAMES.join(", "));
+    process::exit(1);
+}
+
+//words of a list, lowered so they can be compared with built words
+fn read_word_set(path: &str) -> HashSet<String> {
+    match source_for_path(Path::new(path)).words() {
+        Ok(words) => words.iter().map(|w| w.trim().to_lowercase()).collect(),
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
         }
     }
-    coordinates
 }
 
-fn pretty_print_boards() {
-    //make sure boards are up to date with current letters
-    //run through boards and add any letters that are not in the board
-    for mut board in BOARDS.lock().unwrap().iter_mut() {
-        let diff = remove_from_tiles(&board.tiles, &MASTER_LETTERS.lock().unwrap());
-        board.remaining.push_str(&diff);
-        board.tiles = MASTER_LETTERS.lock().unwrap().clone();
-    }
-    
-    let boards = BOARDS.lock().unwrap();
-    for i in 0..boards.len() {
-        pretty_print_board(&boards[i]);
+//word list rule flags, shared by dict build and loading the dictionary
+//returns false if the flag is not a rule
+fn set_rule(rules: &mut Rules, flag: &str, value: &str) -> bool {
+    match flag {
+        "--min-length" => rules.min_length = value.parse().unwrap_or_else(|_| usage()),
+        "--max-length" => rules.max_length = value.parse().unwrap_or_else(|_| usage()),
+        "--blocklist" => rules.blocklist = read_word_set(value),
+        "--proper-nouns" => rules.proper_nouns = read_word_set(value),
+        _ => return false,
     }
+    true
 }
 
-fn pretty_print_board(b: &Board) {
+//clean up a raw word list and write it in the format the output path ends with
+//proper nouns and blocked words are read from the lists that go with the raw list unless their flags are passed
+fn dict_build(args: &[String]) {
+    let [input, output, flags @ ..] = args else {
+        usage();
+    };
 
-    //can easily find min and max x and y values from start of words
-    let min_x = b.word_positions.iter().map(|x| x.0).min().unwrap();
-    let min_y = b.word_positions.iter().map(|x| x.1).min().unwrap();
-
-    //max x and y values are found by adding the length of the word to the start of the word
-    //get max x
-    let mut x_coordinates = Vec::new();
-    for i in 0..b.word_positions.len() {
-        //if word is horizontal
-        if b.word_positions[i].2 == 0 {
-            x_coordinates.push(b.word_positions[i].0 + b.words[i].len() as i32);
-        }
-        else {
-            x_coordinates.push(b.word_positions[i].0);
+    let mut rules = Rules::default();
+    let mut rule_flags = Vec::new();
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        let Some(value) = flags.next() else {
+            usage();
+        };
+        if !set_rule(&mut rules, flag, value) {
+            usage();
         }
+        rule_flags.push(flag.clone());
     }
+    default_rule_lists(&mut rules, &rule_flags, Path::new(input));
 
-    let max_x = x_coordinates.iter().max().unwrap();
-
-    //get max y
-    let mut y_coordinates = Vec::new();
-    for i in 0..b.word_positions.len() {
-        //if word is vertical
-        if b.word_positions[i].2 == 1 {
-            y_coordinates.push(b.word_positions[i].1 + b.words[i].len() as i32);
-        }
-        else {
-            y_coordinates.push(b.word_positions[i].1);
+    let raw = match source_for_path(Path::new(input)).words() {
+        Ok(raw) => raw,
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
         }
+    };
+    let (words, report) = build(&raw, &rules);
+    if let Err(e) = write_word_list(Path::new(output), &words) {
+        println!("{}", e);
+        process::exit(1);
     }
+    println!("{}", report);
+    println!("wrote {}", output);
+}
 
-    let max_y = y_coordinates.iter().max().unwrap();
-    
-    let x_offset = -1 * min_x;
-    let y_offset = -1 * min_y;
-
-    let x_size = max_x - min_x + 1;
-    let y_size = max_y - min_y + 1;
+//word list from the flag, then the environment variable, then words.txt in the current directory
+fn word_list_path(flag: Option<String>) -> PathBuf {
+    flag.or_else(|| env::var(DICT_VAR).ok()).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("words.txt"))
+}
 
-    let mut board = vec![vec![' '; x_size as usize]; y_size as usize];
+//per user directory the house rules are saved in, like ~/.local/share/banana_bot
+fn data_dir() -> PathBuf {
+    let var = |name| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    var("XDG_DATA_HOME")
+        .or_else(|| var("APPDATA"))
+        .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+        .unwrap_or_default()
+        .join("banana_bot")
+}
 
-    for i in 0..b.word_positions.len() {
-        let word = &b.words[i];
-        let positions = &b.word_positions[i];
-        let coordinates = get_word_coordinates(word, positions.0, positions.1, positions.2 , x_offset, y_offset);
-        for j in 0..word.len() {
-            board[coordinates[j].1 as usize][coordinates[j].0 as usize] = word.chars().nth(j).unwrap();
+//words of a list that doesn't have to exist, no file means no words
+fn read_optional_list(path: &Path) -> Vec<String> {
+    match source_for_path(path).words() {
+        Ok(words) => words,
+        Err(DictionaryError::Missing(_)) => Vec::new(),
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
         }
     }
+}
 
-    //TODO place remaining letters here
+//proper nouns and blocked words from the lists that go with the word list, for the rules whose flag was not passed
+fn default_rule_lists(rules: &mut Rules, passed: &[String], list: &Path) {
+    let read = |name| read_optional_list(&companion_path(list, name)).iter().map(|w| w.trim().to_lowercase()).collect();
+    if !passed.iter().any(|flag| flag == "--proper-nouns") {
+        rules.proper_nouns = read(PROPER_NOUNS_LIST);
+    }
+    if !passed.iter().any(|flag| flag == "--blocklist") {
+        rules.blocklist = read(BLOCKLIST);
+    }
+}
 
+fn main() {
+    let mut dict = None;
+    let mut two_letter = None;
+    let mut allow = None;
+    let mut deny = None;
+    let mut scorer = None;
+    let mut rules = Rules::default();
+    let mut rule_flags = Vec::new();
+    let mut config = SearchConfig::default();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("dict") {
+        match args.get(1).map(String::as_str) {
+            Some("build") => dict_build(&args[2..]),
+            _ => usage(),
+        }
+        return;
+    }
 
-    for i in 0..y_size {
-        for j in 0..x_size {
-            print!("{}", board[i as usize][j as usize]);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dict" => dict = Some(args.next().unwrap_or_else(|| usage())),
+            "--two-letter" => two_letter = Some(args.next().unwrap_or_else(|| usage())),
+            "--allow" => allow = Some(args.next().map(PathBuf::from).unwrap_or_else(|| usage())),
+            "--deny" => deny = Some(args.next().map(PathBuf::from).unwrap_or_else(|| usage())),
+            "--scorer" => match args.next().as_deref().and_then(scorer_by_name) {
+                Some(s) => scorer = Some(s),
+                None => usage(),
+            },
+            flag => {
+                let (Some(setting), Some(value)) = (flag.strip_prefix("--"), args.next()) else {
+                    usage();
+                };
+                if set_rule(&mut rules, flag, &value) {
+                    rule_flags.push(flag.to_string());
+                    continue;
+                }
+                if let Err(e) = config.set(setting, &value) {
+                    println!("{}", e);
+                    usage();
+                }
+            }
         }
-        println!("");
     }
 
-    println!("");
-    println!("Remaining letters: {}", b.remaining);
+    //load the dictionary before the first solve instead of during it
+    let dict = word_list_path(dict);
+    default_rule_lists(&mut rules, &rule_flags, &dict);
+    let words = source_for_path(&dict);
+    let two_letter = two_letter.or_else(|| env::var(TWO_LETTER_VAR).ok())
+        .map(|path| source_for_path(Path::new(&path)))
+        .or_else(|| default_two_letter(&dict));
+    let mut dictionary = match Dictionary::load(words.as_ref(), two_letter.as_deref(), &rules) {
+        Ok(dictionary) => dictionary,
+        Err(e) => {
+            println!("Can't load the dictionary: {}", e);
+            if matches!(&e, DictionaryError::Missing(path) if *path == dict) {
+                println!("Pass the word list with --dict <path> or set {}.", DICT_VAR);
+            }
+            process::exit(1);
+        }
+    };
 
-    println!("--------------------");
-   
-}
+    let dir = data_dir();
+    let house = HouseRules {
+        allow: allow.unwrap_or_else(|| dir.join("allow.txt")),
+        deny: deny.unwrap_or_else(|| dir.join("deny.txt")),
+    };
+    //deny comes last so a word on both lists can't be played
+    for word in read_optional_list(&house.allow) {
+        if !dictionary.allow(&word) {
+            println!("Skipped '{}' on the allow list, it has letters that are not tiles", word);
+        }
+    }
+    for word in read_optional_list(&house.deny) {
+        dictionary.deny(&word);
+    }
 
-fn main() {
-    init_game();
+    let mut game = Game::new(Arc::new(dictionary));
+    *game.config_mut() = config;
+    if let Some(scorer) = scorer {
+        game.set_scorer(scorer);
+    }
+    init_game(&mut game, &house);
 }
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use crate::board::{Direction, Grid, Placement};
+use crate::dictionary::Dictionary;
+use crate::tiles::Tiles;
+
+//word the move generator found a place for
+#[derive(Clone)]
+pub struct Move {
+    pub placement: Placement,
+    pub used: Tiles, //letters that have to come from tiles
+}
+
+//state for generating moves in one direction
+//follows Appel and Jacobson: every move is built outwards from an anchor,
+//an empty square next to a letter already on the board
+struct MoveGen<'a> {
+    dictionary: &'a Dictionary,
+    grid: &'a Grid,
+    direction: Direction,
+    rack: [u8; 26], //tiles still available while a word is being built
+    cross_checks: HashMap<(i32, i32), u32>, //bitmask of letters allowed in each empty square
+    anchor: (i32, i32), //anchor the current word is built from
+    moves: Vec<Move>,
+}
+
+//empty squares next to a letter, in a fixed order
+pub fn find_anchors(grid: &Grid) -> Vec<(i32, i32)> {
+    let mut anchors = Vec::new();
+    for &(x, y) in grid.cells.keys() {
+        for (ax, ay) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            if grid.get(ax, ay).is_none() {
+                anchors.push((ax, ay));
+            }
+        }
+    }
+    anchors.sort();
+    anchors.dedup();
+    anchors
+}
+
+//letters that can be laid down on their own somewhere on the grid, bit i is letter i
+//much cheaper than generating the moves of every letter one at a time
+pub fn playable_letters(dictionary: &Dictionary, grid: &Grid) -> u32 {
+    let mut playable = 0;
+    for (x, y) in find_anchors(grid) {
+        for i in 0..26 {
+            if playable & (1 << i) != 0 {
+                continue;
+            }
+            let c = (b'a' + i as u8) as char;
+            let valid = [Direction::Across, Direction::Down].iter().all(|&direction| {
+                let run = grid.run_through(x, y, direction, c);
+                run.len() == 1 || dictionary.is_word(&run)
+            });
+            if valid {
+                playable |= 1 << i;
+            }
+        }
+    }
+    playable
+}
+
+//every legal placement on the grid that uses at least one of the letters
+//on an empty grid words are placed horizontally through (0,0)
+pub fn generate_moves(dictionary: &Dictionary, grid: &Grid, letters: &Tiles) -> Vec<Move> {
+    let rack = letters.counts();
+
+    let anchors = if grid.is_empty() { vec![(0, 0)] } else { find_anchors(grid) };
+    let directions = if grid.is_empty() { vec![Direction::Across] } else { vec![Direction::Across, Direction::Down] };
+
+    let mut moves = Vec::new();
+    for direction in directions {
+        let mut gen = MoveGen {
+            dictionary,
+            grid,
+            direction,
+            rack,
+            cross_checks: HashMap::new(),
+            anchor: (0, 0),
+            moves: Vec::new(),
+        };
+        for &anchor in &anchors {
+            gen.anchor = anchor;
+            gen.moves_at_anchor(&anchors);
+        }
+        moves.append(&mut gen.moves);
+    }
+    moves
+}
+
+impl MoveGen<'_> {
+    fn moves_at_anchor(&mut self, anchors: &[(i32, i32)]) {
+        let (dx, dy) = self.direction.step();
+        let (ax, ay) = self.anchor;
+
+        if self.grid.get(ax - dx, ay - dy).is_some() {
+            //letters before the anchor are already on the board and have to start the word
+            let mut sx = ax - dx;
+            let mut sy = ay - dy;
+            while self.grid.get(sx - dx, sy - dy).is_some() {
+                sx -= dx;
+                sy -= dy;
+            }
+
+            let mut prefix = String::new();
+            let mut node = 0;
+            while (sx, sy) != (ax, ay) {
+                let c = self.grid.get(sx, sy).unwrap();
+                match self.dictionary.trie.child(node, c) {
+                    Some(child) => node = child,
+                    None => return,
+                }
+                prefix.push(c);
+                sx += dx;
+                sy += dy;
+            }
+            self.extend_right(&mut prefix, node, ax, ay);
+        }
+        else {
+            //word can start on any of the empty squares before the anchor
+            //up to the previous anchor, those squares have no neighbours to check
+            let tiles: u32 = self.rack.iter().map(|&n| n as u32).sum();
+            let mut limit = 0;
+            let mut cx = ax - dx;
+            let mut cy = ay - dy;
+            while limit + 1 < tiles && self.grid.get(cx, cy).is_none() && !anchors.contains(&(cx, cy)) {
+                limit += 1;
+                cx -= dx;
+                cy -= dy;
+            }
+            self.left_part(&mut String::new(), 0, limit);
+        }
+    }
+
+    //build every prefix from the rack that can come before the anchor
+    fn left_part(&mut self, partial: &mut String, node: usize, limit: u32) {
+        let (ax, ay) = self.anchor;
+        self.extend_right(partial, node, ax, ay);
+
+        if limit == 0 {
+            return;
+        }
+        for i in 0..26 {
+            let c = (b'a' + i as u8) as char;
+            if self.rack[i] == 0 {
+                continue;
+            }
+            if let Some(child) = self.dictionary.trie.child(node, c) {
+                self.rack[i] -= 1;
+                partial.push(c);
+                self.left_part(partial, child, limit - 1);
+                partial.pop();
+                self.rack[i] += 1;
+            }
+        }
+    }
+
+    //continue the word from square (x,y), through the anchor and past it
+    fn extend_right(&mut self, partial: &mut String, node: usize, x: i32, y: i32) {
+        let (dx, dy) = self.direction.step();
+
+        match self.grid.get(x, y) {
+            None => {
+                if (x, y) != self.anchor && self.dictionary.trie.is_terminal(node) {
+                    self.record(partial, x, y);
+                }
+
+                let allowed = self.cross_check(x, y);
+                for i in 0..26 {
+                    let c = (b'a' + i as u8) as char;
+                    if self.rack[i] == 0 || allowed & (1 << i) == 0 {
+                        continue;
+                    }
+                    if let Some(child) = self.dictionary.trie.child(node, c) {
+                        self.rack[i] -= 1;
+                        partial.push(c);
+                        self.extend_right(partial, child, x + dx, y + dy);
+                        partial.pop();
+                        self.rack[i] += 1;
+                    }
+                }
+            }
+            Some(c) => {
+                if let Some(child) = self.dictionary.trie.child(node, c) {
+                    partial.push(c);
+                    self.extend_right(partial, child, x + dx, y + dy);
+                    partial.pop();
+                }
+            }
+        }
+    }
+
+    //word ends just before (x,y)
+    fn record(&mut self, word: &str, x: i32, y: i32) {
+        let (dx, dy) = self.direction.step();
+        let len = word.len() as i32;
+        let placement = Placement::new(word, (x - dx * len, y - dy * len), self.direction);
+
+        let mut used = Tiles::new();
+        for ((cx, cy), c) in placement.cells() {
+            if self.grid.get(cx, cy).is_none() {
+                used.add(c).expect("trie words only use letters a-z");
+            }
+        }
+
+        self.moves.push(Move { placement, used });
+    }
+
+    //letters that can go in an empty square without making an invalid cross word
+    fn cross_check(&mut self, x: i32, y: i32) -> u32 {
+        if let Some(&allowed) = self.cross_checks.get(&(x, y)) {
+            return allowed;
+        }
+
+        let cross = self.direction.cross();
+        let (cdx, cdy) = cross.step();
+        let mut allowed = (1 << 26) - 1;
+        if self.grid.get(x - cdx, y - cdy).is_some() || self.grid.get(x + cdx, y + cdy).is_some() {
+            allowed = 0;
+            for i in 0..26 {
+                let run = self.grid.run_through(x, y, cross, (b'a' + i as u8) as char);
+                if self.dictionary.is_word(&run) {
+                    allowed |= 1 << i;
+                }
+            }
+        }
+
+        self.cross_checks.insert((x, y), allowed);
+        allowed
+    }
+}
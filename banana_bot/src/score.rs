@@ -0,0 +1,145 @@
+use std::thread;
+
+use crate::board::Board;
+use crate::bunch::{flexibility, Bunch};
+use crate::dictionary::{letter_rarity, Dictionary};
+use crate::movegen::find_anchors;
+
+//ranks finished boards, higher scores are better boards to play
+pub trait BoardScorer: Send + Sync {
+    fn name(&self) -> &str;
+    fn score(&self, dictionary: &Dictionary, bunch: &Bunch, b: &Board) -> f64;
+}
+
+//number of tiles on the board
+pub struct TilesUsed;
+
+//number of empty cells next to a tile, more places to build off after a peel
+pub struct OpenAnchors;
+
+//share of the bounding box that is filled, a tight board is easier to keep track of
+pub struct Compactness;
+
+//rarity of the tiles still in hand, q and z left over are worse than an e
+pub struct DifficultLetters;
+
+//chance the board takes the next tile we draw without moving anything
+pub struct Flexibility;
+
+//sum of other scorers, each multiplied by its weight
+pub struct Weighted {
+    pub scorers: Vec<(f64, Box<dyn BoardScorer>)>,
+}
+
+//names that can be passed to scorer_by_name
+pub static SCORER_NAMES: [&str; 6] = ["tiles", "anchors", "compact", "difficult", "flexibility", "weighted"];
+
+impl BoardScorer for TilesUsed {
+    fn name(&self) -> &str {
+        "tiles"
+    }
+
+    fn score(&self, _dictionary: &Dictionary, _bunch: &Bunch, b: &Board) -> f64 {
+        b.grid.cells.len() as f64
+    }
+}
+
+impl BoardScorer for OpenAnchors {
+    fn name(&self) -> &str {
+        "anchors"
+    }
+
+    fn score(&self, _dictionary: &Dictionary, _bunch: &Bunch, b: &Board) -> f64 {
+        if b.grid.is_empty() {
+            return 0.0;
+        }
+        find_anchors(&b.grid).len() as f64
+    }
+}
+
+impl BoardScorer for Compactness {
+    fn name(&self) -> &str {
+        "compact"
+    }
+
+    fn score(&self, _dictionary: &Dictionary, _bunch: &Bunch, b: &Board) -> f64 {
+        if b.grid.is_empty() {
+            return 0.0;
+        }
+        let area = (b.grid.max_x - b.grid.min_x + 1) * (b.grid.max_y - b.grid.min_y + 1);
+        b.grid.cells.len() as f64 / area as f64
+    }
+}
+
+impl BoardScorer for DifficultLetters {
+    fn name(&self) -> &str {
+        "difficult"
+    }
+
+    fn score(&self, _dictionary: &Dictionary, _bunch: &Bunch, b: &Board) -> f64 {
+        -(b.remaining.letters().map(letter_rarity).sum::<u32>() as f64)
+    }
+}
+
+impl BoardScorer for Flexibility {
+    fn name(&self) -> &str {
+        "flexibility"
+    }
+
+    fn score(&self, dictionary: &Dictionary, bunch: &Bunch, b: &Board) -> f64 {
+        flexibility(dictionary, b, bunch)
+    }
+}
+
+impl BoardScorer for Weighted {
+    fn name(&self) -> &str {
+        "weighted"
+    }
+
+    fn score(&self, dictionary: &Dictionary, bunch: &Bunch, b: &Board) -> f64 {
+        self.scorers.iter().map(|(weight, scorer)| weight * scorer.score(dictionary, bunch, b)).sum()
+    }
+}
+
+//tiles used comes first, the rest mostly break ties between boards that use the same number of tiles
+impl Default for Weighted {
+    fn default() -> Weighted {
+        Weighted {
+            scorers: vec![
+                (1.0, Box::new(TilesUsed)),
+                (0.1, Box::new(DifficultLetters)),
+                (0.5, Box::new(Flexibility)),
+                (0.2, Box::new(Compactness)),
+                (0.01, Box::new(OpenAnchors)),
+            ],
+        }
+    }
+}
+
+pub fn scorer_by_name(name: &str) -> Option<Box<dyn BoardScorer>> {
+    match name {
+        "tiles" => Some(Box::new(TilesUsed)),
+        "anchors" => Some(Box::new(OpenAnchors)),
+        "compact" => Some(Box::new(Compactness)),
+        "difficult" => Some(Box::new(DifficultLetters)),
+        "flexibility" => Some(Box::new(Flexibility)),
+        "weighted" => Some(Box::new(Weighted::default())),
+        _ => None,
+    }
+}
+
+//boards sorted best first by the scorer, scored on up to threads threads at once
+//boards with equal scores keep their order
+pub fn rank_boards(dictionary: &Dictionary, bunch: &Bunch, scorer: &dyn BoardScorer, boards: Vec<Board>, threads: usize) -> Vec<Board> {
+    let chunk = boards.len().div_ceil(threads.max(1)).max(1);
+    let scores: Vec<f64> = thread::scope(|scope| {
+        let workers: Vec<_> = boards.chunks(chunk).map(|chunk| scope.spawn(move || {
+            chunk.iter().map(|b| scorer.score(dictionary, bunch, b)).collect::<Vec<f64>>()
+        })).collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("scoring thread panicked")).collect()
+    });
+
+    let mut scored: Vec<(f64, Board)> = scores.into_iter().zip(boards).collect();
+    scored.sort_by(|(s1, _), (s2, _)| s2.total_cmp(s1));
+    scored.into_iter().map(|(_, b)| b).collect()
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+//stops a running solve from another thread, the boards found so far are kept
+//a cancel that arrives between solves stops the next one
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+//how much of the search tree the last solve got through
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SearchStats {
+    pub nodes: usize, //boards that were expanded
+    pub boards: usize, //boards the search ended on, before invalid and duplicate ones are dropped
+    pub cut_off: usize, //boards left unexpanded because time ran out or the search was cancelled
+    pub transpositions: usize, //boards skipped because the same position was already searched
+    pub timed_out: bool,
+    pub cancelled: bool,
+    pub elapsed: Duration,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SearchStats {
+    //totals of searches that ran side by side
+    pub fn add(&mut self, other: &SearchStats) {
+        self.nodes += other.nodes;
+        self.boards += other.boards;
+        self.cut_off += other.cut_off;
+        self.transpositions += other.transpositions;
+        self.timed_out |= other.timed_out;
+        self.cancelled |= other.cancelled;
+    }
+
+    //true if every branch the config allows was followed
+    pub fn is_complete(&self) -> bool {
+        self.cut_off == 0
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expanded {} boards and finished {} in {:.2}s", self.nodes, self.boards, self.elapsed.as_secs_f64())?;
+        if self.transpositions > 0 {
+            write!(f, ", skipped {} repeated positions", self.transpositions)?;
+        }
+        if self.cancelled {
+            write!(f, ", cancelled with {} boards left unexpanded", self.cut_off)?;
+        }
+        else if self.timed_out {
+            write!(f, ", ran out of time with {} boards left unexpanded", self.cut_off)?;
+        }
+        Ok(())
+    }
+}
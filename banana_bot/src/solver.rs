@@ -0,0 +1,795 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::board::{validate, Board, Direction, Grid, InvalidWord, Placement, Position};
+use crate::bunch::Bunch;
+use crate::config::SearchConfig;
+use crate::dictionary::{find_longest_words, word_rank, Dictionary, TieBreak};
+use crate::dump::{dump_advice, DumpAdvice};
+use crate::movegen::{generate_moves, Move};
+use crate::score::{rank_boards, BoardScorer, Weighted};
+use crate::search::{CancelToken, SearchStats};
+use crate::tiles::{InputError, Tiles};
+
+//one game of bananagrams: the letters dealt so far and the boards found for them
+//the dictionary is shared so several games can run in one process
+pub struct Game {
+    dictionary: Arc<Dictionary>,
+    letters: Tiles, //all letters user has logged so far
+    boards: Vec<Board>, //currently solved boards
+    tie_break: TieBreak, //secondary ordering for find_longest_words
+    table: Option<Board>, //board actually laid out on the table
+    bunch: Bunch, //tiles the game started with, tracks the ones we have been dealt
+    scorer: Box<dyn BoardScorer>, //orders the boards, best first
+    config: SearchConfig,
+    cancel: CancelToken,
+    stats: SearchStats, //how far the last solve got
+}
+
+//board after a peel and how many tiles that were already on the table have to be moved for it
+pub struct PeelResult {
+    pub board: Board,
+    pub moved: usize,
+}
+
+#[derive(Debug)]
+pub enum PeelError {
+    NoTable, //no board has been kept yet
+    Input(InputError),
+}
+
+impl Game {
+    pub fn new(dictionary: Arc<Dictionary>) -> Game {
+        Game {
+            dictionary,
+            letters: Tiles::new(),
+            boards: Vec::new(),
+            tie_break: TieBreak::Rarity,
+            table: None,
+            bunch: Bunch::standard(),
+            scorer: Box::new(Weighted::default()),
+            config: SearchConfig::default(),
+            cancel: CancelToken::new(),
+            stats: SearchStats::default(),
+        }
+    }
+
+    //add letters typed in by the user and return the tiles that were added
+    //nothing is added if any character is rejected
+    pub fn add_letters(&mut self, input: &str) -> Result<Tiles, InputError> {
+        let tiles = Tiles::parse(input)?;
+        self.letters.add_all(&tiles).map_err(InputError::Tiles)?;
+        self.bunch.see(&tiles);
+        Ok(tiles)
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    //for house rule changes, the next solve uses them
+    pub fn dictionary_mut(&mut self) -> &mut Dictionary {
+        Arc::make_mut(&mut self.dictionary)
+    }
+
+    pub fn letters(&self) -> &Tiles {
+        &self.letters
+    }
+
+    pub fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    //forget the letters, the boards and the table
+    pub fn reset(&mut self) {
+        self.letters = Tiles::new();
+        self.boards.clear();
+        self.table = None;
+        self.bunch.clear_seen();
+    }
+
+    pub fn bunch(&self) -> &Bunch {
+        &self.bunch
+    }
+
+    //switch to another edition of the game, letters dealt so far count as seen
+    pub fn set_bunch(&mut self, bunch: Bunch) {
+        self.bunch = bunch;
+        self.bunch.clear_seen();
+        self.bunch.see(&self.letters);
+    }
+
+    pub fn scorer(&self) -> &dyn BoardScorer {
+        self.scorer.as_ref()
+    }
+
+    //reorders the current boards with the new scorer
+    pub fn set_scorer(&mut self, scorer: Box<dyn BoardScorer>) {
+        self.scorer = scorer;
+        self.sort_boards();
+    }
+
+    pub fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut SearchConfig {
+        &mut self.config
+    }
+
+    //clone of the token that stops the running solve
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    pub fn table(&self) -> Option<&Board> {
+        self.table.as_ref()
+    }
+
+    //remember board i as the one laid out on the table
+    pub fn keep_board(&mut self, i: usize) -> Option<&Board> {
+        self.table = Some(self.boards.get(i)?.clone());
+        self.table.as_ref()
+    }
+
+    //rank the leftover tiles of the board on the table, or the best board if none is kept
+    pub fn dump_advice(&mut self) -> Option<Vec<DumpAdvice>> {
+        self.sync_boards();
+        let board = self.table.as_ref().or(self.boards.first())?;
+        Some(dump_advice(&self.dictionary, board))
+    }
+
+    //add the peeled letters to the board on the table and look for ways to use every tile
+    //first only adds or extends words, if tiles are still left over it also tries picking up
+    //one word at a time and rebuilding from there
+    //stops early like solve, each result is then the best board found for it so far
+    //results are best first and also become the current boards, the best one becomes the board on the table
+    pub fn peel(&mut self, input: &str) -> Result<Vec<PeelResult>, PeelError> {
+        let table = self.table.clone().ok_or(PeelError::NoTable)?;
+        self.add_letters(input).map_err(PeelError::Input)?;
+        let start = self.synced(&table);
+
+        let clock = Instant::now();
+        let deadline = self.config.time_budget.map(|budget| clock + budget);
+        self.stats = SearchStats::default();
+
+        let branching = [self.config.peel_branching];
+        //picking up each word gives its own result, one can't skip a layout because another got there first
+        let limits = Limits {
+            min_word_length: 1,
+            max_depth: self.config.peel_depth,
+            until_complete: true,
+            own_table: true,
+        };
+
+        let first = [Task { board: start.clone(), branching: &branching, memo: true }];
+        let (found, stats) = self.run_tasks(&first, limits, deadline);
+        self.stats.add(&stats);
+        let mut results = vec![fewest_remaining(&start, &start, found)];
+
+        if !results[0].remaining.is_empty() && start.placements.len() > 1 {
+            let mut tasks = Vec::new();
+            for i in 0..start.placements.len() {
+                if let Some(picked_up) = start.without_word(i) {
+                    //taking tiles away can leave a cross word that is no longer valid
+                    if validate(&self.dictionary, &picked_up).is_err() {
+                        continue;
+                    }
+                    tasks.push(Task { board: picked_up, branching: &branching, memo: true });
+                }
+            }
+            let (mut found, stats) = self.run_tasks(&tasks, limits, deadline);
+            self.stats.add(&stats);
+            found.sort_by_key(|(i, _)| *i);
+            for (i, boards) in found {
+                results.push(fewest_remaining(&start, &tasks[i].board, vec![(i, boards)]));
+            }
+        }
+
+        self.stats.elapsed = clock.elapsed();
+        self.cancel.reset();
+
+        //a picked up tile that goes back where it was doesn't count as moved
+        let mut results: Vec<PeelResult> = results.into_iter()
+            .map(|board| PeelResult { moved: board.moved_from(&start), board })
+            .collect();
+        results.sort_by_key(|r| (r.board.remaining.len(), r.moved));
+        //picking up different words can end in the same layout, the one that moves the fewest tiles is kept
+        let transpose = self.config.dedupe_transposed;
+        let mut seen = HashSet::new();
+        results.retain(|r| seen.insert(r.board.position(transpose)));
+        results.truncate(self.config.max_boards);
+        self.boards = results.iter().map(|r| r.board.clone()).collect();
+        self.table = self.boards.first().cloned();
+        Ok(results)
+    }
+
+    pub fn reset_boards(&mut self) {
+        self.boards.clear();
+    }
+
+    fn empty_board(&self) -> Board {
+        Board {
+            tiles: self.letters,
+            remaining: self.letters,
+            placements: Vec::new(),
+            grid: Grid::new(),
+        }
+    }
+
+    //make sure boards are up to date with current letters
+    //run through boards and the table and add any letters that are not in the board
+    pub fn sync_boards(&mut self) {
+        self.boards = self.boards.iter().map(|b| self.synced(b)).collect();
+        self.table = self.table.as_ref().map(|b| self.synced(b));
+    }
+
+    //copy of the board with the letters added since it was made as remaining tiles
+    fn synced(&self, b: &Board) -> Board {
+        let mut b = b.clone();
+        let diff = self.letters.difference(&b.tiles);
+        b.remaining.add_all(&diff).expect("remaining tiles are never more than the letters");
+        b.tiles = self.letters;
+        b
+    }
+
+    //finishing pass: attach as many leftover tiles as possible to every board
+    //boards keep the tiles placed so far when the time budget runs out or the cancel token is used
+    pub fn place_remaining(&mut self) {
+        self.sync_boards();
+        let clock = Instant::now();
+        let deadline = self.config.time_budget.map(|budget| clock + budget);
+        let limits = Limits {
+            min_word_length: 1,
+            max_depth: usize::MAX,
+            until_complete: false,
+            own_table: false,
+        };
+
+        let transpositions = Mutex::new(HashMap::new());
+        let mut searcher = self.searcher(limits, deadline, &transpositions);
+        let boards: Vec<Board> = self.boards.iter().map(|b| searcher.place_remaining(b)).collect();
+        let mut stats = searcher.stats;
+
+        stats.elapsed = clock.elapsed();
+        self.stats = stats;
+        self.boards = boards;
+        self.cancel.reset();
+        self.sort_boards();
+    }
+
+    //find boards for the current letters, returns the invalid words of every board that had to be dropped
+    //stops early when the time budget runs out or the cancel token is used, keeping the boards found so far
+    pub fn solve(&mut self) -> Vec<Vec<InvalidWord>> {
+        let start = Instant::now();
+        let deadline = self.config.time_budget.map(|budget| start + budget);
+
+        //a greedy pass first so there is a finished board to fall back on if time runs out
+        let mut tasks = self.tasks(&[1], &[1], false);
+        tasks.append(&mut self.tasks(&self.config.branching, &self.config.restart_branching, true));
+
+        let limits = Limits {
+            min_word_length: self.config.min_word_length,
+            max_depth: usize::MAX,
+            until_complete: false,
+            own_table: false,
+        };
+        let (mut found, stats) = self.run_tasks(&tasks, limits, deadline);
+        self.stats = stats;
+
+        //in task order the boards come out the same on every run
+        if self.config.deterministic {
+            found.sort_by_key(|(i, _)| *i);
+        }
+        for (_, mut boards) in found {
+            self.boards.append(&mut boards);
+        }
+
+        self.stats.elapsed = start.elapsed();
+        self.cancel.reset();
+
+        self.purge_old_boards();
+        //boards that would be challenged at the table
+        let dropped = self.purge_invalid_boards();
+        //the greedy pass and the full search can end on the same layout
+        self.remove_duplicates();
+
+        self.sort_boards();
+
+        dropped
+    }
+
+    //search every task on the worker threads, each thread takes the next task until there are none left
+    //returns the boards of every task with the task's index, in the order the tasks finished
+    fn run_tasks(&self, tasks: &[Task], limits: Limits, deadline: Option<Instant>) -> (Vec<(usize, Vec<Board>)>, SearchStats) {
+        let threads = self.threads();
+        let next = AtomicUsize::new(0);
+        let transpositions = Mutex::new(HashMap::new());
+        let mut found = Vec::new();
+        let mut total = SearchStats::default();
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.min(tasks.len())).map(|_| scope.spawn(|| {
+                let mut searcher = self.searcher(limits, deadline, &transpositions);
+                let mut found = Vec::new();
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(task) = tasks.get(i) else {
+                        break;
+                    };
+                    searcher.local.clear();
+                    searcher.complete = false;
+                    searcher.construct(&task.board, task.branching, 0, task.memo);
+                    found.push((i, std::mem::take(&mut searcher.boards)));
+                }
+                (found, searcher.stats)
+            })).collect();
+
+            for worker in workers {
+                let (mut boards, stats) = worker.join().expect("search thread panicked");
+                found.append(&mut boards);
+                total.add(&stats);
+            }
+        });
+        (found, total)
+    }
+
+    fn searcher<'a>(&'a self, limits: Limits, deadline: Option<Instant>, transpositions: &'a Mutex<HashMap<Position, Budget>>) -> Searcher<'a> {
+        Searcher {
+            dictionary: &self.dictionary,
+            config: &self.config,
+            tie_break: self.tie_break,
+            limits,
+            deadline,
+            cancel: &self.cancel,
+            transpositions,
+            local: HashMap::new(),
+            boards: Vec::new(),
+            complete: false,
+            stats: SearchStats::default(),
+        }
+    }
+
+    fn threads(&self) -> usize {
+        match self.config.threads {
+            0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        }
+    }
+
+    //subtrees the search can run independently: building on each of the current boards,
+    //or from each of the first words if there are none
+    fn tasks<'a>(&self, branching: &'a [usize], restart_branching: &'a [usize], memo: bool) -> Vec<Task<'a>> {
+        let mut tasks = Vec::new();
+
+        //check if there are any boards
+        if self.boards.is_empty() {
+            for b in self.first_words() {
+                tasks.push(Task { board: b, branching, memo });
+            }
+        }
+        else {
+            for board in &self.boards {
+                tasks.push(Task { board: self.synced(board), branching, memo });
+            }
+
+            //Check refactor condition
+            for b in self.first_words() {
+                tasks.push(Task { board: b, branching: restart_branching, memo });
+            }
+        }
+        tasks
+    }
+
+    //boards with one of the longest words laid down
+    fn first_words(&self) -> Vec<Board> {
+        let b = self.empty_board();
+        let words = find_longest_words(&self.dictionary, &self.letters, self.config.first_words, self.tie_break);
+        words.iter().map(|word| {
+            let mut b2 = b.clone();
+            let word_tiles = Tiles::from_letters(word).expect("formable words only use letters a-z");
+            b2.remaining = self.letters.subtract(&word_tiles).expect("formable words only use tiles from the rack");
+            let placement = Placement::new(word, (0, 0), Direction::Across);
+            b2.grid.place(&placement);
+            b2.placements.push(placement);
+            b2
+        }).collect()
+    }
+
+    //best boards first by the scorer, only the top few are kept
+    fn sort_boards(&mut self) {
+        let boards = std::mem::take(&mut self.boards);
+        self.boards = rank_boards(&self.dictionary, &self.bunch, self.scorer.as_ref(), boards, self.threads());
+        self.boards.truncate(self.config.max_boards);
+    }
+
+    fn purge_old_boards(&mut self) {
+        //remove boards that have less letters than the game
+        let letters = self.letters.len();
+        self.boards.retain(|b| b.tiles.len() >= letters);
+    }
+
+    fn purge_invalid_boards(&mut self) -> Vec<Vec<InvalidWord>> {
+        let mut dropped = Vec::new();
+        let dictionary = &self.dictionary;
+        self.boards.retain(|b| match validate(dictionary, b) {
+            Ok(()) => true,
+            Err(invalid) => {
+                dropped.push(invalid);
+                false
+            }
+        });
+        dropped
+    }
+
+    //remove boards that ended up with the same layout through different word orders
+    fn remove_duplicates(&mut self) {
+        let transpose = self.config.dedupe_transposed;
+        let mut seen = HashSet::new();
+        self.boards.retain(|b| seen.insert(b.position(transpose)));
+    }
+}
+
+//subtree of a solve that one thread searches on its own
+struct Task<'a> {
+    board: Board,
+    branching: &'a [usize],
+    memo: bool, //skip positions that were already searched
+}
+
+//how far the search goes from the board of a task, the same for every task
+#[derive(Clone, Copy)]
+struct Limits {
+    min_word_length: usize, //shorter words are left for the finishing pass
+    max_depth: usize, //words laid down on top of the task's board at most
+    until_complete: bool, //a task is done once it finds a board that uses every tile
+    own_table: bool, //a task only skips positions it searched itself, so its boards don't depend on the other tasks
+}
+
+//branches a search still follows below a position, from its level down
+#[derive(Clone, Debug)]
+struct Budget {
+    branching: Vec<usize>, //branches per level, the last one repeats
+    depth: usize, //levels left before max_depth
+}
+
+impl Budget {
+    fn new(branching: &[usize], level: usize, max_depth: usize) -> Budget {
+        let from = level.min(branching.len().saturating_sub(1));
+        Budget {
+            branching: branching[from..].to_vec(),
+            depth: max_depth.saturating_sub(level),
+        }
+    }
+
+    //true if searching with this budget follows every branch the other one would
+    fn covers(&self, other: &Budget) -> bool {
+        let levels = self.branching.len().max(other.branching.len());
+        self.depth >= other.depth
+            && (0..levels).all(|l| SearchConfig::branches(&self.branching, l) >= SearchConfig::branches(&other.branching, l))
+    }
+}
+
+//one depth first search over some of the tasks of a solve, each thread has its own
+struct Searcher<'a> {
+    dictionary: &'a Dictionary,
+    config: &'a SearchConfig,
+    tie_break: TieBreak,
+    limits: Limits,
+    deadline: Option<Instant>, //end of the time budget
+    cancel: &'a CancelToken,
+    transpositions: &'a Mutex<HashMap<Position, Budget>>, //positions searched by every thread and how widely
+    local: HashMap<Position, Budget>, //positions searched by the task that is running, used instead when the search is deterministic or tasks have their own table
+    boards: Vec<Board>, //boards found for the task that is running
+    complete: bool, //the task that is running found a board that uses every tile
+    stats: SearchStats,
+}
+
+impl Searcher<'_> {
+    //recursively find words that connect to the board with the remaining letters
+    //paramaters: Board struct, branches to follow per level, the current level and whether to skip repeated positions
+    //if no more words can be found that connect or no more remaining letters, add board to boards
+    fn construct(&mut self, b: &Board, branching: &[usize], level: usize, memo: bool) {
+        if self.limits.until_complete && self.complete {
+            return;
+        }
+
+        //the same layout reached through another word order has already been searched
+        if memo && !self.first_visit(b, branching, level) {
+            self.stats.transpositions += 1;
+            return;
+        }
+
+        //if no more letters, add board to boards
+        if b.remaining.is_empty() {
+            self.stats.boards += 1;
+            self.boards.push(b.clone());
+            self.complete = true;
+            return;
+        }
+
+        //deep enough, keep the board as it is
+        if level >= self.limits.max_depth {
+            self.stats.boards += 1;
+            self.boards.push(b.clone());
+            return;
+        }
+
+        //if out of time keep the board as it is
+        if self.should_stop() {
+            self.stats.boards += 1;
+            self.stats.cut_off += 1;
+            self.boards.push(b.clone());
+            return;
+        }
+        self.stats.nodes += 1;
+
+        //every legal placement through the letters already on the board
+        let mut moves: Vec<Move> = generate_moves(self.dictionary, &b.grid, &b.remaining);
+
+        //short words are skipped
+        let min_word_length = self.limits.min_word_length;
+        moves.retain(|m| m.placement.word.len() >= min_word_length);
+
+        //if no words can be placed, add board to boards
+        if moves.is_empty() {
+            self.stats.boards += 1;
+            self.boards.push(b.clone());
+            return;
+        }
+
+        //only follow the placements that use the most tiles
+        sort_moves(&mut moves, self.tie_break);
+        moves.truncate(SearchConfig::branches(branching, level));
+
+        for m in moves {
+            let b2 = play_move(b, &m);
+            self.construct(&b2, branching, level + 1, memo);
+        }
+    }
+
+    //greedily attach leftover tiles one placement at a time until none of them fit
+    //every placement comes from the move generator so all cross words it forms are valid,
+    //which also covers single tiles and two or three letter words made from the leftovers
+    fn place_remaining(&mut self, b: &Board) -> Board {
+        let mut board = b.clone();
+        while !board.remaining.is_empty() && !board.grid.is_empty() {
+            if self.should_stop() {
+                self.stats.cut_off += 1;
+                break;
+            }
+            self.stats.nodes += 1;
+            let mut moves = generate_moves(self.dictionary, &board.grid, &board.remaining);
+            sort_moves(&mut moves, self.tie_break);
+            match moves.first() {
+                Some(m) => board = play_move(&board, m),
+                None => break,
+            }
+        }
+        self.stats.boards += 1;
+        board
+    }
+
+    //which thread gets to a position first depends on timing, so a deterministic search
+    //only skips positions that came up earlier in the same task, like tasks with their own table
+    //a position counts as new again when this visit would search it wider than the last one did
+    fn first_visit(&mut self, b: &Board, branching: &[usize], level: usize) -> bool {
+        let position = b.position(self.config.dedupe_transposed);
+        let budget = Budget::new(branching, level, self.limits.max_depth);
+        let mut shared;
+        let table = if self.config.deterministic || self.limits.own_table {
+            &mut self.local
+        }
+        else {
+            shared = self.transpositions.lock().expect("transposition table lock poisoned");
+            &mut *shared
+        };
+        match table.get_mut(&position) {
+            Some(seen) if seen.covers(&budget) => false,
+            Some(seen) => {
+                *seen = budget;
+                true
+            }
+            None => {
+                table.insert(position, budget);
+                true
+            }
+        }
+    }
+
+    //true once the time budget is used up or the solve was cancelled
+    fn should_stop(&mut self) -> bool {
+        if self.cancel.is_cancelled() {
+            self.stats.cancelled = true;
+        }
+        else if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            self.stats.timed_out = true;
+        }
+        self.stats.cancelled || self.stats.timed_out
+    }
+}
+
+//placements that use the most tiles first
+//position is the last tie break so the order does not depend on hash map order
+fn sort_moves(moves: &mut [Move], tie_break: TieBreak) {
+    moves.sort_by(|m1, m2| {
+        m2.used.len().cmp(&m1.used.len())
+            .then_with(|| word_rank(&m2.placement.word, tie_break).cmp(&word_rank(&m1.placement.word, tie_break)))
+            .then_with(|| (m1.placement.origin, m1.placement.direction).cmp(&(m2.placement.origin, m2.placement.direction)))
+    });
+}
+
+//copy of the board with the move laid down, the move must only use remaining tiles
+fn play_move(b: &Board, m: &Move) -> Board {
+    let mut b2 = b.clone();
+    b2.remaining = b.remaining.subtract(&m.used).expect("moves only use tiles from the rack");
+    b2.grid.place(&m.placement);
+    b2.placements.push(m.placement.clone());
+    debug_assert!(b2.is_consistent(), "move does not line up with the board");
+    b2
+}
+
+//board found from start that uses the most tiles, start itself if the search found nothing new
+//ties go to the board that moves the fewest tiles of the table, then to the first one found
+fn fewest_remaining(table: &Board, start: &Board, found: Vec<(usize, Vec<Board>)>) -> Board {
+    found.into_iter()
+        .flat_map(|(_, boards)| boards)
+        .min_by_key(|b| (b.remaining.len(), b.moved_from(table)))
+        .unwrap_or_else(|| start.clone())
+}
+
+impl fmt::Display for PeelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PeelError::NoTable => write!(f, "no board has been kept as the one on the table"),
+            PeelError::Input(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for PeelError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bunch::STANDARD_DISTRIBUTION;
+    use crate::lexicon::Rules;
+    use crate::source::{default_two_letter, PlainList};
+    use std::path::Path;
+    use std::time::Duration;
+
+    //xorshift so every run draws the same racks and moves
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        //letters drawn from a full standard bunch
+        fn rack(&mut self, size: usize) -> String {
+            let mut bunch: Vec<char> = STANDARD_DISTRIBUTION.iter().enumerate()
+                .flat_map(|(i, &n)| std::iter::repeat_n((b'a' + i as u8) as char, n as usize))
+                .collect();
+            (0..size).map(|_| bunch.swap_remove(self.below(bunch.len()))).collect()
+        }
+    }
+
+    fn dictionary() -> Dictionary {
+        let list = Path::new(env!("CARGO_MANIFEST_DIR")).join("words.txt");
+        let two_letter = default_two_letter(&list);
+        Dictionary::load(&PlainList { path: list }, two_letter.as_deref(), &Rules::default()).unwrap()
+    }
+
+    fn game() -> Game {
+        let mut game = Game::new(Arc::new(dictionary()));
+        *game.config_mut() = SearchConfig::speed();
+        game.config_mut().time_budget = Some(Duration::from_millis(500));
+        game
+    }
+
+    //words agree on every cell they share, every letter belongs to a word and every run is a word
+    fn assert_valid(dictionary: &Dictionary, b: &Board) {
+        let words: Vec<String> = b.placements.iter().map(|p| p.to_string()).collect();
+        for (i, p) in b.placements.iter().enumerate() {
+            for other in &b.placements[i + 1..] {
+                assert!(p.agrees_with(other), "{} and {} disagree where they cross", p, other);
+            }
+        }
+        let covered: HashSet<(i32, i32)> = b.placements.iter().flat_map(|p| p.cells().map(|(cell, _)| cell)).collect();
+        assert_eq!(covered.len(), b.grid.cells.len(), "letters on the grid that are not in a word: {}", words.join(", "));
+        assert!(b.grid.is_connected(), "board falls apart: {}", words.join(", "));
+        assert!(validate(dictionary, b).is_ok(), "board has a run that is not a word: {}", words.join(", "));
+    }
+
+    #[test]
+    fn random_moves_keep_boards_valid() {
+        let dictionary = dictionary();
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for _ in 0..40 {
+            let size = 8 + rng.below(15);
+            let rack = Tiles::from_letters(&rng.rack(size)).unwrap();
+            let first = find_longest_words(&dictionary, &rack, 5, TieBreak::Alphabetical);
+            if first.is_empty() {
+                continue;
+            }
+            let word = &first[rng.below(first.len())];
+            let placement = Placement::new(word, (0, 0), Direction::Across);
+            let mut b = Board {
+                tiles: rack,
+                remaining: rack.subtract(&Tiles::from_letters(word).unwrap()).unwrap(),
+                placements: Vec::new(),
+                grid: Grid::new(),
+            };
+            b.grid.place(&placement);
+            b.placements.push(placement);
+
+            for _ in 0..10 {
+                let moves = generate_moves(&dictionary, &b.grid, &b.remaining);
+                if moves.is_empty() {
+                    break;
+                }
+                b = play_move(&b, &moves[rng.below(moves.len())]);
+                assert_valid(&dictionary, &b);
+            }
+        }
+    }
+
+    #[test]
+    fn random_racks_solve_to_valid_boards() {
+        let mut game = game();
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        for _ in 0..6 {
+            let size = 10 + rng.below(15);
+            game.reset();
+            game.add_letters(&rng.rack(size)).unwrap();
+            assert!(game.solve().is_empty());
+            for b in game.boards() {
+                assert_valid(&game.dictionary, b);
+            }
+
+            game.place_remaining();
+            for b in game.boards() {
+                assert_valid(&game.dictionary, b);
+            }
+        }
+    }
+
+    #[test]
+    fn peeled_boards_are_valid() {
+        let mut game = game();
+        game.add_letters("abcdeeilmnoprstuaeiou").unwrap();
+        game.solve();
+        game.keep_board(0).unwrap();
+        for peel in ["e", "qz", "jkvw"] {
+            let results = game.peel(peel).unwrap();
+            for result in &results {
+                assert_valid(&game.dictionary, &result.board);
+            }
+            //the next peel and dump advice build on the best board
+            let table = game.table().unwrap();
+            assert_eq!(table.position(false), results[0].board.position(false));
+            assert_eq!(table.tiles, *game.letters());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+//two letter list that goes with a word list, words.txt has two letter entries like qc and tv that are not words
+pub static TWO_LETTER_LIST: &str = "two_letter";
+
+//first bytes of a binary word list, followed by a format version
+static BINARY_MAGIC: &[u8; 4] = b"BBWL";
+static BINARY_VERSION: u8 = 1;
+
+//where a word list comes from
+//words come back in the order they are listed, the dictionary keeps that order for ties
+pub trait WordSource {
+    fn words(&self) -> Result<Vec<String>, DictionaryError>;
+}
+
+//one word per line
+pub struct PlainList {
+    pub path: PathBuf,
+}
+
+//plain list compressed with gzip
+pub struct GzipList {
+    pub path: PathBuf,
+}
+
+//words written by write_binary_list, the same words as a plain list in a length prefixed layout
+//a header then every word as its length in one byte followed by its letters
+pub struct BinaryList {
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum DictionaryError {
+    Missing(PathBuf), //no file at the path
+    Io(PathBuf, io::Error),
+    Malformed(PathBuf, String), //file was read but is not a word list, with what was wrong
+}
+
+impl WordSource for PlainList {
+    fn words(&self) -> Result<Vec<String>, DictionaryError> {
+        read_lines(&self.path, open(&self.path)?)
+    }
+}
+
+impl WordSource for GzipList {
+    fn words(&self) -> Result<Vec<String>, DictionaryError> {
+        read_lines(&self.path, GzDecoder::new(open(&self.path)?))
+    }
+}
+
+impl WordSource for BinaryList {
+    fn words(&self) -> Result<Vec<String>, DictionaryError> {
+        let mut bytes = Vec::new();
+        open(&self.path)?.read_to_end(&mut bytes).map_err(|e| DictionaryError::Io(self.path.clone(), e))?;
+        let malformed = |reason: &str| DictionaryError::Malformed(self.path.clone(), reason.to_string());
+
+        if bytes.len() < 9 || &bytes[..4] != BINARY_MAGIC {
+            return Err(malformed("not a banana_bot binary word list"));
+        }
+        if bytes[4] != BINARY_VERSION {
+            return Err(malformed(&format!("binary word list version {} is not supported", bytes[4])));
+        }
+        let count = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+
+        let mut words = Vec::with_capacity(count);
+        let mut rest = &bytes[9..];
+        for _ in 0..count {
+            let (&len, tail) = rest.split_first().ok_or_else(|| malformed("list ends early"))?;
+            if tail.len() < len as usize {
+                return Err(malformed("list ends early"));
+            }
+            let (word, tail) = tail.split_at(len as usize);
+            let word = String::from_utf8(word.to_vec()).map_err(|_| malformed("list has a word that is not utf-8"))?;
+            words.push(word);
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            return Err(malformed("list has data after the last word"));
+        }
+        Ok(words)
+    }
+}
+
+//source for the file format the path ends with: .gz is gzip, .bin is a binary word list, anything else is plain
+pub fn source_for_path(path: &Path) -> Box<dyn WordSource> {
+    let path = path.to_path_buf();
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => Box::new(GzipList { path }),
+        Some("bin") => Box::new(BinaryList { path }),
+        _ => Box::new(PlainList { path }),
+    }
+}
+
+//list that goes with one word list, named after it in the same directory, like words.two_letter.txt for words.txt
+//these lists are written for the entries of one word list, another word list doesn't pick them up
+pub fn companion_path(list: &Path, name: &str) -> PathBuf {
+    let file = list.file_name().and_then(|f| f.to_str()).unwrap_or_default();
+    let stem = file.split('.').next().unwrap_or_default();
+    list.with_file_name(format!("{}.{}.txt", stem, name))
+}
+
+//two letter list that goes with the word list, used when no other two letter list is given
+//None if there isn't one, then the two letter words of the word list are used
+pub fn default_two_letter(list: &Path) -> Option<Box<dyn WordSource>> {
+    let path = companion_path(list, TWO_LETTER_LIST);
+    path.exists().then(|| source_for_path(&path))
+}
+
+//write words in the format BinaryList reads
+pub fn write_binary_list<W: Write>(words: &[String], mut writer: W) -> io::Result<()> {
+    let invalid = |reason: String| io::Error::new(io::ErrorKind::InvalidInput, reason);
+    let count = u32::try_from(words.len()).map_err(|_| invalid("too many words for a binary word list".to_string()))?;
+
+    writer.write_all(BINARY_MAGIC)?;
+    writer.write_all(&[BINARY_VERSION])?;
+    writer.write_all(&count.to_le_bytes())?;
+    for word in words {
+        let len = u8::try_from(word.len()).map_err(|_| invalid(format!("'{}' is too long for a binary word list", word)))?;
+        writer.write_all(&[len])?;
+        writer.write_all(word.as_bytes())?;
+    }
+    writer.flush()
+}
+
+//write words in the format source_for_path picks for the path
+pub fn write_word_list(path: &Path, words: &[String]) -> Result<(), DictionaryError> {
+    let io_error = |e| DictionaryError::Io(path.to_path_buf(), e);
+    let file = File::create(path).map_err(io_error)?;
+    let writer = BufWriter::new(file);
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => write_lines(words, GzEncoder::new(writer, Compression::best())).and_then(|e| e.finish()?.flush()),
+        Some("bin") => write_binary_list(words, writer),
+        _ => write_lines(words, writer).and_then(|mut w| w.flush()),
+    }
+    .map_err(io_error)
+}
+
+fn write_lines<W: Write>(words: &[String], mut writer: W) -> io::Result<W> {
+    for word in words {
+        writeln!(writer, "{}", word)?;
+    }
+    Ok(writer)
+}
+
+fn open(path: &Path) -> Result<File, DictionaryError> {
+    File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => DictionaryError::Missing(path.to_path_buf()),
+        _ => DictionaryError::Io(path.to_path_buf(), e),
+    })
+}
+
+//trailing whitespace and windows line endings are dropped, blank lines are skipped
+fn read_lines<R: Read>(path: &Path, reader: R) -> Result<Vec<String>, DictionaryError> {
+    let mut words = Vec::new();
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => DictionaryError::Malformed(path.to_path_buf(), format!("line {} is not utf-8", i + 1)),
+            _ => DictionaryError::Io(path.to_path_buf(), e),
+        })?;
+        let word = line.trim_end();
+        if !word.is_empty() {
+            words.push(word.to_string());
+        }
+    }
+    Ok(words)
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DictionaryError::Missing(path) => write!(f, "no word list at {}", path.display()),
+            DictionaryError::Io(path, e) => write!(f, "can't read {}: {}", path.display(), e),
+            DictionaryError::Malformed(path, reason) => write!(f, "{} is not a word list: {}", path.display(), reason),
+        }
+    }
+}
+
+impl Error for DictionaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DictionaryError::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::error::Error;
+use std::fmt;
+
+//multiset of tiles, stored as the number of each letter a-z
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Tiles {
+    counts: [u8; 26],
+}
+
+//letters typed in that can't be added
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputError {
+    Rejected(Vec<char>), //characters that are not letters or separators
+    Tiles(TilesError), //letters that don't fit with the tiles already entered
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TilesError {
+    NotATile(char), //character is not a letter a-z
+    Missing(char), //letter is not in the tiles
+    TooMany(char), //more than 255 of the letter
+}
+
+impl Tiles {
+    pub fn new() -> Tiles {
+        Tiles {
+            counts: [0; 26],
+        }
+    }
+
+    //parse letters typed in by the user
+    //upper case is lowered, commas and whitespace separate letters, anything else is rejected
+    pub fn parse(input: &str) -> Result<Tiles, InputError> {
+        let mut tiles = Tiles::new();
+        let mut rejected = Vec::new();
+        for c in input.chars() {
+            if c == ',' || c.is_whitespace() {
+                continue;
+            }
+            match tiles.add(c.to_ascii_lowercase()) {
+                Ok(()) => {}
+                Err(TilesError::NotATile(_)) => rejected.push(c),
+                Err(e) => return Err(InputError::Tiles(e)),
+            }
+        }
+
+        if rejected.is_empty() {
+            Ok(tiles)
+        }
+        else {
+            Err(InputError::Rejected(rejected))
+        }
+    }
+
+    //every character has to be a letter a-z
+    pub fn from_letters(letters: &str) -> Result<Tiles, TilesError> {
+        let mut tiles = Tiles::new();
+        for c in letters.chars() {
+            tiles.add(c)?;
+        }
+        Ok(tiles)
+    }
+
+    pub fn counts(&self) -> [u8; 26] {
+        self.counts
+    }
+
+    pub fn count(&self, c: char) -> u8 {
+        match letter_index(c) {
+            Some(i) => self.counts[i],
+            None => 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|&n| n as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|&n| n == 0)
+    }
+
+    pub fn add(&mut self, c: char) -> Result<(), TilesError> {
+        let i = letter_index(c).ok_or(TilesError::NotATile(c))?;
+        self.counts[i] = self.counts[i].checked_add(1).ok_or(TilesError::TooMany(c))?;
+        Ok(())
+    }
+
+    //nothing is added if any letter would go over 255
+    pub fn add_all(&mut self, other: &Tiles) -> Result<(), TilesError> {
+        let mut counts = self.counts;
+        for (i, (count, &n)) in counts.iter_mut().zip(other.counts.iter()).enumerate() {
+            *count = count.checked_add(n).ok_or(TilesError::TooMany(index_letter(i)))?;
+        }
+        self.counts = counts;
+        Ok(())
+    }
+
+    //take one tile out, fails if it is not there
+    pub fn remove(&mut self, c: char) -> Result<(), TilesError> {
+        let i = letter_index(c).ok_or(TilesError::NotATile(c))?;
+        if self.counts[i] == 0 {
+            return Err(TilesError::Missing(c));
+        }
+        self.counts[i] -= 1;
+        Ok(())
+    }
+
+    //tiles left after taking out other, fails with the first letter that is not there
+    pub fn subtract(&self, other: &Tiles) -> Result<Tiles, TilesError> {
+        let mut tiles = *self;
+        for i in 0..26 {
+            if other.counts[i] > tiles.counts[i] {
+                return Err(TilesError::Missing(index_letter(i)));
+            }
+            tiles.counts[i] -= other.counts[i];
+        }
+        Ok(tiles)
+    }
+
+    //tiles in self that are not in other
+    pub fn difference(&self, other: &Tiles) -> Tiles {
+        let mut tiles = *self;
+        for (count, &n) in tiles.counts.iter_mut().zip(other.counts.iter()) {
+            *count = count.saturating_sub(n);
+        }
+        tiles
+    }
+
+    //true if every tile in self is also in other
+    pub fn is_subset(&self, other: &Tiles) -> bool {
+        self.counts.iter().zip(other.counts.iter()).all(|(needed, have)| needed <= have)
+    }
+
+    //every tile in alphabetical order
+    pub fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.counts.iter().enumerate().flat_map(|(i, &n)| std::iter::repeat_n(index_letter(i), n as usize))
+    }
+}
+
+impl fmt::Display for Tiles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.letters() {
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TilesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TilesError::NotATile(c) => write!(f, "'{}' is not a tile", c),
+            TilesError::Missing(c) => write!(f, "no '{}' tile left", c),
+            TilesError::TooMany(c) => write!(f, "there can't be more than 255 '{}' tiles", c),
+        }
+    }
+}
+
+impl Error for TilesError {}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Rejected(rejected) => {
+                let rejected: Vec<String> = rejected.iter().map(|c| format!("'{}'", c)).collect();
+                write!(f, "{} can't be entered, only letters A-Z separated by spaces or commas", rejected.join(", "))
+            }
+            InputError::Tiles(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+fn letter_index(c: char) -> Option<usize> {
+    if c.is_ascii_lowercase() {
+        Some((c as u8 - b'a') as usize)
+    }
+    else {
+        None
+    }
+}
+
+fn index_letter(i: usize) -> char {
+    (b'a' + i as u8) as char
+}